@@ -2,12 +2,12 @@ use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Write};
 use std::str::FromStr;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Card<const JOKERS: bool = false> {
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+enum Card {
     Ace,
     King,
     Queen,
-    JackOrJoker,
+    Jack,
     Ten,
     Nine,
     Eight,
@@ -19,45 +19,37 @@ enum Card<const JOKERS: bool = false> {
     Two,
 }
 
-impl<const JOKERS: bool> PartialOrd<Self> for Card<JOKERS> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl<const JOKERS: bool> Ord for Card<JOKERS> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if JOKERS {
-            let a = if let Card::JackOrJoker = self {
-                13
-            } else {
-                *self as u8
-            };
-            let b = if let Card::JackOrJoker = other {
-                13
-            } else {
-                *other as u8
-            };
-            a.cmp(&b)
-        } else {
-            (*self as u8).cmp(&(*other as u8))
-        }
-    }
+impl Card {
+    const ALL: [Card; 13] = [
+        Card::Ace,
+        Card::King,
+        Card::Queen,
+        Card::Jack,
+        Card::Ten,
+        Card::Nine,
+        Card::Eight,
+        Card::Seven,
+        Card::Six,
+        Card::Five,
+        Card::Four,
+        Card::Three,
+        Card::Two,
+    ];
 }
 
-impl<const JOKERS: bool> Display for Card<JOKERS> {
+impl Display for Card {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_char((*self).into())
     }
 }
 
-impl<const JOKERS: bool> From<Card<JOKERS>> for char {
-    fn from(value: Card<JOKERS>) -> Self {
+impl From<Card> for char {
+    fn from(value: Card) -> Self {
         match value {
             Card::Ace => 'A',
             Card::King => 'K',
             Card::Queen => 'Q',
-            Card::JackOrJoker => 'J',
+            Card::Jack => 'J',
             Card::Ten => 'T',
             Card::Nine => '9',
             Card::Eight => '8',
@@ -71,7 +63,7 @@ impl<const JOKERS: bool> From<Card<JOKERS>> for char {
     }
 }
 
-impl<const JOKERS: bool> TryFrom<char> for Card<JOKERS> {
+impl TryFrom<char> for Card {
     type Error = anyhow::Error;
     fn try_from(value: char) -> Result<Self, Self::Error> {
         use anyhow::anyhow;
@@ -80,7 +72,7 @@ impl<const JOKERS: bool> TryFrom<char> for Card<JOKERS> {
             'A' => C::Ace,
             'K' => C::King,
             'Q' => C::Queen,
-            'J' => C::JackOrJoker,
+            'J' => C::Jack,
             'T' => C::Ten,
             '9' => C::Nine,
             '8' => C::Eight,
@@ -96,104 +88,576 @@ impl<const JOKERS: bool> TryFrom<char> for Card<JOKERS> {
     }
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
-struct Hand<const JOKERS: bool> {
-    hand_type: HandType,
-    cards: [Card<JOKERS>; 5],
+/// Where wild cards sit when breaking ties between hands of the same type
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum WildRank {
+    Natural,
+    Lowest,
+    Highest,
 }
 
-impl<const JOKERS: bool> TryFrom<[Card<JOKERS>; 5]> for Hand<JOKERS> {
-    type Error = anyhow::Error;
-    fn try_from(cards: [Card<JOKERS>; 5]) -> Result<Self, Self::Error> {
+impl FromStr for WildRank {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "natural" => Self::Natural,
+            "lowest" => Self::Lowest,
+            "highest" => Self::Highest,
+            _ => Err(anyhow::anyhow!("invalid wild rank {s:?}"))?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct RuleSet {
+    // strongest first, always a permutation of Card::ALL
+    order: [Card; 13],
+    wilds: Vec<Card>,
+    wild_rank: WildRank,
+}
+
+impl RuleSet {
+    fn new(order: [Card; 13], wilds: Vec<Card>, wild_rank: WildRank) -> anyhow::Result<Self> {
+        if let Some(missing) = Card::ALL.iter().find(|card| !order.contains(card)) {
+            anyhow::bail!("order is missing {missing}");
+        }
         Ok(Self {
-            hand_type: cards.into(),
+            order,
+            wilds,
+            wild_rank,
+        })
+    }
+
+    fn standard() -> Self {
+        Self::new(Card::ALL, Vec::new(), WildRank::Natural).expect("should be valid preset")
+    }
+
+    fn jokers() -> Self {
+        Self::new(Card::ALL, vec![Card::Jack], WildRank::Lowest).expect("should be valid preset")
+    }
+
+    fn is_wild(&self, card: Card) -> bool {
+        self.wilds.contains(&card)
+    }
+
+    /// Tie-break strength of a card, lower is stronger like the derived orderings
+    fn strength(&self, card: Card) -> u8 {
+        let pos = self
+            .order
+            .iter()
+            .position(|&x| x == card)
+            .expect("order should have every card") as u8;
+        match self.wild_rank {
+            WildRank::Lowest if self.is_wild(card) => pos + 13,
+            WildRank::Highest if !self.is_wild(card) => pos + 13,
+            _ => pos,
+        }
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = anyhow::Error;
+    // Expects order then optional wilds and wild rank like "AKQJT98765432 J lowest"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let order: [Card; 13] = parts
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("should have card order"))?
+            .chars()
+            .map(Card::try_from)
+            .collect::<Result<Vec<_>, _>>()?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("order should have 13 cards"))?;
+        let wilds = match parts.next() {
+            Some(wilds_str) => wilds_str
+                .chars()
+                .map(Card::try_from)
+                .collect::<Result<_, _>>()?,
+            None => Vec::new(),
+        };
+        let wild_rank = parts.next().map_or(Ok(WildRank::Natural), str::parse)?;
+        Self::new(order, wilds, wild_rank)
+    }
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+struct Hand {
+    hand_type: HandType,
+    strengths: Vec<u8>,
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    fn new(cards: Vec<Card>, rules: &RuleSet) -> Self {
+        Self {
+            hand_type: HandType::with_rules(&cards, rules),
+            strengths: cards.iter().map(|&card| rules.strength(card)).collect(),
             cards,
+        }
+    }
+
+    fn parse(s: &str, rules: &RuleSet) -> anyhow::Result<Self> {
+        let cards = s
+            .chars()
+            .map(Card::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        if cards.is_empty() {
+            anyhow::bail!("hand should have cards");
+        }
+        Ok(Self::new(cards, rules))
+    }
+
+    /// The cards with every wild swapped for whatever makes the best hand, and the type those
+    /// cards get when played without wilds, e.g. KTJJT becomes KTTTT under joker rules
+    fn best_substitution(&self, rules: &RuleSet) -> (Vec<Card>, HandType) {
+        let count = |card: Card| self.cards.iter().filter(|&&x| x == card).count();
+        let target = self
+            .cards
+            .iter()
+            .filter(|&&card| !rules.is_wild(card))
+            .max_by_key(|&&card| (count(card), std::cmp::Reverse(rules.strength(card))))
+            .or_else(|| rules.order.iter().find(|&&card| !rules.is_wild(card)))
+            .copied()
+            .unwrap_or(rules.order[0]);
+        let cards: Vec<Card> = self
+            .cards
+            .iter()
+            .map(|&card| if rules.is_wild(card) { target } else { card })
+            .collect();
+        let hand_type = HandType::with_rules(&cards, &RuleSet::standard());
+        (cards, hand_type)
+    }
+}
+
+impl Display for Hand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.cards.iter().try_for_each(|card| write!(f, "{card}"))
+    }
+}
+
+impl FromStr for Hand {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s, &RuleSet::standard())
+    }
+}
+
+/// Multiplicities of each card sorted descending, wilds going to the biggest group, so any hand
+/// size ranks the same way the named five card types do
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct HandType(Vec<usize>);
+
+impl HandType {
+    fn with_rules(cards: &[Card], rules: &RuleSet) -> Self {
+        let mut card_counts = [0usize; 13];
+        let mut wilds = 0;
+        for &card in cards {
+            if rules.is_wild(card) {
+                wilds += 1;
+            } else {
+                card_counts[card as usize] += 1;
+            }
+        }
+        let mut counts: Vec<usize> = card_counts.into_iter().filter(|&x| x > 0).collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        match counts.first_mut() {
+            Some(max_count) => *max_count += wilds,
+            None => counts.push(wilds),
+        }
+        Self(counts)
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some(match self.0.as_slice() {
+            [5] => "five of a kind",
+            [4, 1] => "four of a kind",
+            [3, 2] => "full house",
+            [3, 1, 1] => "three of a kind",
+            [2, 2, 1] => "two pair",
+            [2, 1, 1, 1] => "one pair",
+            [1, 1, 1, 1, 1] => "high card",
+            _ => None?,
         })
     }
 }
 
+impl PartialOrd<Self> for HandType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HandType {
+    // bigger groups sort first, same as the strongest cards do
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl Display for HandType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => f.pad(name),
+            None => {
+                let counts: Vec<String> = self.0.iter().map(usize::to_string).collect();
+                f.pad(&counts.join("+"))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+struct Bid<H = Hand>(H, usize);
+
+impl Bid {
+    // Expects hand space bet like "32T3K 765"
+    fn parse(s: &str, rules: &RuleSet) -> anyhow::Result<Self> {
+        let (hand_str, bet_str) = s
+            .rsplit_once(' ')
+            .ok_or_else(|| anyhow::anyhow!("should have space between hand and bet"))?;
+        Ok(Self(Hand::parse(hand_str, rules)?, bet_str.parse()?))
+    }
+}
+
+fn parse_bids(input: &str, rules: &RuleSet) -> anyhow::Result<Vec<Bid>> {
+    let mut hand_len = None;
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let bid =
+                Bid::parse(line, rules).map_err(|e| anyhow::anyhow!("line {}: {e}", i + 1))?;
+            let len = *hand_len.get_or_insert(bid.0.cards.len());
+            if bid.0.cards.len() != len {
+                anyhow::bail!(
+                    "line {}: hand has {} cards, expected {len}",
+                    i + 1,
+                    bid.0.cards.len()
+                );
+            }
+            Ok(bid)
+        })
+        .collect()
+}
+
+fn rank_winnings<H: Ord>(mut bids: Vec<Bid<H>>) -> usize {
+    bids.sort_unstable();
+    bids.reverse();
+    bids.iter().enumerate().map(|(i, x)| x.1 * (i + 1)).sum()
+}
+
+fn total_winnings(input: &str, rules: &RuleSet) -> usize {
+    rank_winnings(parse_bids(input, rules).expect("should be valid bids"))
+}
+
+/// One line per bid from weakest to strongest with what its wilds became and what it won
+fn explain_winnings(input: &str, rules: &RuleSet) -> String {
+    let mut bids = parse_bids(input, rules).expect("should be valid bids");
+    bids.sort_unstable();
+    bids.reverse();
+    let mut out = String::new();
+    let mut total = 0;
+    for (i, Bid(hand, bet)) in bids.iter().enumerate() {
+        let rank = i + 1;
+        let (cards, hand_type) = hand.best_substitution(rules);
+        let substituted: String = cards.into_iter().map(char::from).collect();
+        total += bet * rank;
+        writeln!(
+            out,
+            "{rank:>5}: {hand} -> {substituted} {hand_type:<15} {bet:>5} * {rank} = {}",
+            bet * rank
+        )
+        .expect("should write to string");
+    }
+    writeln!(out, "total: {total}").expect("should write to string");
+    out
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+impl TryFrom<char> for Suit {
+    type Error = anyhow::Error;
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Ok(match value {
+            's' => Self::Spades,
+            'h' => Self::Hearts,
+            'd' => Self::Diamonds,
+            'c' => Self::Clubs,
+            _ => Err(anyhow::anyhow!("invalid suit"))?,
+        })
+    }
+}
+
+/// A card that may or may not say its suit, unsuited ones just never make flushes
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct SuitedCard {
+    card: Card,
+    suit: Option<Suit>,
+}
+
+impl FromStr for SuitedCard {
+    type Err = anyhow::Error;
+    // Expects card then optional suit like "As" or "T"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let card = Card::try_from(chars.next().ok_or_else(|| anyhow::anyhow!("empty card"))?)?;
+        let suit = chars.next().map(Suit::try_from).transpose()?;
+        if chars.next().is_some() {
+            anyhow::bail!("card {s:?} too long");
+        }
+        Ok(Self { card, suit })
+    }
+}
+
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
-enum HandType {
+enum PokerHandType {
     FiveOfAKind,
+    StraightFlush,
     FourOfAKind,
     FullHouse,
+    Flush,
+    Straight,
     ThreeOfAKind,
     TwoPair,
     OnePair,
     HighCard,
 }
 
-impl<const JOKERS: bool> From<[Card<JOKERS>; 5]> for HandType {
-    fn from(cards: [Card<JOKERS>; 5]) -> Self {
-        let mut card_counts = [0usize; 13];
-        for card in cards {
-            let count = card_counts
-                .get_mut(card as usize)
-                .expect("should be in range");
-            if !JOKERS && 4.eq(count) {
-                return Self::FiveOfAKind;
+/// Standard poker ranking, where ties go to the best kickers rather than the first card
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct PokerHand {
+    hand_type: PokerHandType,
+    kickers: Vec<Card>,
+    cards: [SuitedCard; 5],
+}
+
+impl PokerHand {
+    fn new(cards: [SuitedCard; 5]) -> Self {
+        let ranks = cards.map(|x| x.card);
+        let HandType(counts) = HandType::with_rules(&ranks, &RuleSet::standard());
+        // strongest groups first, then strongest cards within the same group size
+        let mut kickers: Vec<Card> = ranks.to_vec();
+        kickers.sort_unstable_by_key(|&card| {
+            (
+                std::cmp::Reverse(ranks.iter().filter(|&&x| x == card).count()),
+                card,
+            )
+        });
+        kickers.dedup();
+        let flush = cards[0].suit.is_some() && cards.iter().all(|x| x.suit == cards[0].suit);
+        // a wheel (A5432) is the only straight where the ace plays low
+        let straight_high = match kickers.as_slice() {
+            [Card::Ace, Card::Five, Card::Four, Card::Three, Card::Two] => Some(Card::Five),
+            &[high, .., low] if kickers.len() == 5 && low as usize - high as usize == 4 => {
+                Some(high)
             }
-            *count += 1;
+            _ => None,
+        };
+        use PokerHandType as T;
+        let hand_type = match (counts.as_slice(), straight_high, flush) {
+            ([5], ..) => T::FiveOfAKind,
+            (_, Some(_), true) => T::StraightFlush,
+            ([4, 1], ..) => T::FourOfAKind,
+            ([3, 2], ..) => T::FullHouse,
+            (_, _, true) => T::Flush,
+            (_, Some(_), _) => T::Straight,
+            ([3, 1, 1], ..) => T::ThreeOfAKind,
+            ([2, 2, 1], ..) => T::TwoPair,
+            ([2, 1, 1, 1], ..) => T::OnePair,
+            _ => T::HighCard,
+        };
+        if let Some(high) = straight_high {
+            kickers = vec![high];
+        }
+        Self {
+            hand_type,
+            kickers,
+            cards,
         }
-        let jokers = if JOKERS {
-            std::mem::take(&mut card_counts[Card::<JOKERS>::JackOrJoker as usize])
+    }
+}
+
+impl FromStr for PokerHand {
+    type Err = anyhow::Error;
+    // Expects space separated suited cards like "As Kd 7h 7c 2s", or bare cards like "32T3K"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards: Vec<SuitedCard> = if s.contains(' ') {
+            s.split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()?
         } else {
-            0
+            s.chars()
+                .map(|card| {
+                    Ok(SuitedCard {
+                        card: card.try_into()?,
+                        suit: None,
+                    })
+                })
+                .collect::<anyhow::Result<_>>()?
         };
-        card_counts.sort_unstable();
-        let [.., next_max_count, max_count] = card_counts;
-        match (max_count + jokers, next_max_count) {
-            (5.., _) => Self::FiveOfAKind,
-            (4, _) => Self::FourOfAKind,
-            (3, 2) => Self::FullHouse,
-            (3, 1) => Self::ThreeOfAKind,
-            (2, 2) => Self::TwoPair,
-            (2, 1) => Self::OnePair,
-            _ => Self::HighCard,
-        }
+        Ok(Self::new(cards.try_into().map_err(|cards: Vec<_>| {
+            anyhow::anyhow!("poker hand should have 5 cards, not {}", cards.len())
+        })?))
     }
 }
 
-#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
-struct Bid<const JOKERS: bool>(Hand<JOKERS>, usize);
+impl PartialOrd<Self> for PokerHand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-impl<const JOKERS: bool> FromStr for Bid<JOKERS> {
+impl Ord for PokerHand {
+    // suits never break ties in poker
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.hand_type, &self.kickers).cmp(&(other.hand_type, &other.kickers))
+    }
+}
+
+impl FromStr for Bid<PokerHand> {
     type Err = anyhow::Error;
-    // Expects hand space bet like "32T3K 765"
+    // Expects hand space bet like "As Kd 7h 7c 2s 765"
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (hand_str, bet_str) = s.split_at(5);
-        let cards: [Card<JOKERS>; 5] = hand_str
-            .chars()
-            .map(Card::try_from)
-            .collect::<Result<Vec<_>, _>>()?
-            .try_into()
-            .map_err(|_| anyhow::anyhow!("shouldn't change length or anything"))?;
-        Ok(Self(Hand::try_from(cards)?, bet_str[1..].parse()?))
+        let (hand_str, bet_str) = s
+            .rsplit_once(' ')
+            .ok_or_else(|| anyhow::anyhow!("should have space between hand and bet"))?;
+        Ok(Self(hand_str.parse()?, bet_str.parse()?))
+    }
+}
+
+/// More unknown cards than this many outcomes gets sampled instead of enumerated
+const EXACT_OUTCOME_LIMIT: u64 = 13u64.pow(6);
+const MONTE_CARLO_SAMPLES: u64 = 200_000;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct WinChance {
+    wins: u64,
+    ties: u64,
+    trials: u64,
+    exact: bool,
+}
+
+impl WinChance {
+    fn probability(&self) -> f64 {
+        self.wins as f64 / self.trials as f64
     }
 }
 
+/// splitmix64, just enough randomness for sampling without pulling in a crate
+struct SplitMix64(u64);
+
+impl Iterator for SplitMix64 {
+    type Item = u64;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Some(z ^ (z >> 31))
+    }
+}
+
+/// Chance that `a` beats a hand like `b_pattern` where each '?' is any card with equal odds,
+/// e.g. "KT??T". Exact when there are few enough unknowns, otherwise a seeded Monte Carlo estimate
+fn win_chance(a: &Hand, b_pattern: &str, rules: &RuleSet) -> anyhow::Result<WinChance> {
+    let mut b_cards = b_pattern
+        .chars()
+        .map(|x| match x {
+            '?' => Ok(None),
+            x => Card::try_from(x).map(Some),
+        })
+        .collect::<anyhow::Result<Vec<Option<Card>>>>()?;
+    if b_cards.len() != a.cards.len() {
+        anyhow::bail!(
+            "hands should be the same size, {} vs {}",
+            a.cards.len(),
+            b_cards.len()
+        );
+    }
+    let unknowns: Vec<usize> = (0..b_cards.len())
+        .filter(|&i| b_cards[i].is_none())
+        .collect();
+    let mut chance = WinChance {
+        wins: 0,
+        ties: 0,
+        trials: 0,
+        exact: true,
+    };
+    let mut tally = |b_cards: &[Option<Card>]| {
+        let b = Hand::new(
+            b_cards
+                .iter()
+                .map(|x| x.expect("should be filled"))
+                .collect(),
+            rules,
+        );
+        match a.cmp(&b) {
+            Ordering::Less => chance.wins += 1,
+            Ordering::Equal => chance.ties += 1,
+            Ordering::Greater => (),
+        }
+        chance.trials += 1;
+    };
+    let outcomes = 13u64.checked_pow(unknowns.len() as u32);
+    if outcomes.is_some_and(|x| x <= EXACT_OUTCOME_LIMIT) {
+        // odometer over the unknown positions
+        let mut digits = vec![0usize; unknowns.len()];
+        loop {
+            for (&i, &digit) in unknowns.iter().zip(&digits) {
+                b_cards[i] = Some(Card::ALL[digit]);
+            }
+            tally(&b_cards);
+            let Some(place) = digits.iter().position(|&x| x < 12) else {
+                break;
+            };
+            digits[..place].fill(0);
+            digits[place] += 1;
+        }
+    } else {
+        let mut rng = SplitMix64(b_pattern.len() as u64);
+        for _ in 0..MONTE_CARLO_SAMPLES {
+            for &i in &unknowns {
+                let digit = rng.next().expect("should be infinite") % 13;
+                b_cards[i] = Some(Card::ALL[digit as usize]);
+            }
+            tally(&b_cards);
+        }
+        chance.exact = false;
+    }
+    Ok(chance)
+}
+
 #[aoc(day7, part1)]
 fn part1(input: &str) -> usize {
-    let mut bids: Vec<Bid<false>> = input
-        .lines()
-        .map(str::parse)
-        .map(|x| x.expect("should be valid bid"))
-        .collect();
-    bids.sort_unstable();
-    bids.reverse();
-    bids.iter().enumerate().map(|(i, x)| x.1 * (i + 1)).sum()
+    total_winnings(input, &RuleSet::standard())
 }
 
 #[aoc(day7, part2)]
 fn part2(input: &str) -> usize {
-    let mut bids: Vec<Bid<true>> = input
-        .lines()
-        .map(str::parse)
-        .map(|x| x.expect("should be valid bid"))
-        .collect();
-    bids.sort_unstable();
-    bids.reverse();
-    bids.iter().enumerate().map(|(i, x)| x.1 * (i + 1)).sum()
+    total_winnings(input, &RuleSet::jokers())
+}
+
+#[aoc(day7, part2, explained)]
+fn part2_explained(input: &str) -> usize {
+    let rules = RuleSet::jokers();
+    print!("{}", explain_winnings(input, &rules));
+    total_winnings(input, &rules)
+}
+
+#[aoc(day7, part1, poker)]
+fn part1_poker(input: &str) -> usize {
+    rank_winnings(
+        input
+            .lines()
+            .map(|line| line.parse::<Bid<PokerHand>>().expect("should be valid bid"))
+            .collect(),
+    )
 }
 
 #[cfg(test)]
@@ -215,4 +679,208 @@ QQQJA 483";
     fn part2_example() {
         assert_eq!(part2(INPUT), 5905);
     }
+
+    #[test]
+    fn presets_from_str() {
+        assert_eq!(
+            "AKQJT98765432".parse::<RuleSet>().unwrap(),
+            RuleSet::standard()
+        );
+        assert_eq!(
+            "AKQJT98765432 J lowest".parse::<RuleSet>().unwrap(),
+            RuleSet::jokers()
+        );
+        assert!("AKQJT9876543".parse::<RuleSet>().is_err());
+        assert!("AKQJT98765433".parse::<RuleSet>().is_err());
+    }
+
+    #[test]
+    fn house_rules() {
+        let rules: RuleSet = "AKQJT98765432 2K highest".parse().unwrap();
+        assert_eq!(
+            Hand::parse("32T3K", &rules).unwrap().hand_type,
+            HandType(vec![4, 1])
+        );
+        assert!(rules.strength(Card::Two) < rules.strength(Card::Ace));
+        assert!(rules.strength(Card::King) < rules.strength(Card::Two));
+        assert_eq!(total_winnings(INPUT, &rules), 5510);
+    }
+
+    #[test]
+    fn variable_hand_sizes() {
+        const INPUT_3: &str = "2AA 10
+KKK 20
+Q2J 30
+AK2 40";
+        assert_eq!(part1(INPUT_3), 30 + 2 * 40 + 3 * 10 + 4 * 20);
+        assert_eq!(part2(INPUT_3), 40 + 2 * 10 + 3 * 30 + 4 * 20);
+        const INPUT_7: &str = "2233445 1
+AAAAAKK 2
+AKQT987 3
+JJJJJJJ 4";
+        assert_eq!(part1(INPUT_7), 3 + 2 + 3 * 2 + 4 * 4);
+        assert!(parse_bids("32T3K 765\nKK67 28", &RuleSet::standard()).is_err());
+    }
+
+    #[test]
+    fn hand_type_names() {
+        let names: Vec<String> = [
+            "AAAAA", "AA8AA", "23332", "TTT98", "23432", "A23A4", "23456",
+        ]
+        .iter()
+        .map(|x| x.parse::<Hand>().unwrap().hand_type.to_string())
+        .collect();
+        assert_eq!(
+            names,
+            [
+                "five of a kind",
+                "four of a kind",
+                "full house",
+                "three of a kind",
+                "two pair",
+                "one pair",
+                "high card"
+            ]
+        );
+        assert!(HandType(vec![3, 2]) < HandType(vec![3, 1, 1]));
+        assert_eq!(
+            "KKKQQ22".parse::<Hand>().unwrap().hand_type.to_string(),
+            "3+2+2"
+        );
+    }
+
+    #[test]
+    fn poker_hand_types() {
+        let hand_types: Vec<PokerHandType> = [
+            "AAAAA",
+            "9h Th Jh Qh Kh",
+            "7s 7h 7d 7c Ks",
+            "As Ad Ac 2s 2d",
+            "2c 9c Tc Jc Kc",
+            "As 2d 3c 4h 5s",
+            "Ts 9d 8c 7h 6s",
+            "32T3K",
+            "KTJJT",
+            "Ah Kh Qh Jh 9s",
+            "5h 4h 3h 2h Ah",
+        ]
+        .iter()
+        .map(|x| x.parse::<PokerHand>().unwrap().hand_type)
+        .collect();
+        use PokerHandType as T;
+        assert_eq!(
+            hand_types,
+            [
+                T::FiveOfAKind,
+                T::StraightFlush,
+                T::FourOfAKind,
+                T::FullHouse,
+                T::Flush,
+                T::Straight,
+                T::Straight,
+                T::OnePair,
+                T::TwoPair,
+                T::HighCard,
+                T::StraightFlush,
+            ]
+        );
+        assert!("As Kd 7h 7c".parse::<PokerHand>().is_err());
+        assert!("As Kd 7h 7x 2s".parse::<PokerHand>().is_err());
+    }
+
+    #[test]
+    fn poker_kickers() {
+        let hand = |s: &str| s.parse::<PokerHand>().unwrap();
+        // kickers win, not position
+        assert!(hand("2s Ad Ah Kc 3d") < hand("As Ac 2h Qd 3s"));
+        assert!(hand("KK223") < hand("QQ33A"));
+        assert!(hand("Ts 9d 8c 7h 6s") < hand("As 2d 3c 4h 5s"));
+        assert_eq!(
+            hand("As Kd 7h 7c 2s").cmp(&hand("Ad Kh 7s 7d 2c")),
+            Ordering::Equal
+        );
+        assert_eq!(part1_poker(INPUT), 6440);
+    }
+
+    #[test]
+    fn joker_substitution() {
+        let rules = RuleSet::jokers();
+        let substitute = |s: &str| {
+            let hand = Hand::parse(s, &rules).unwrap();
+            let (cards, hand_type) = hand.best_substitution(&rules);
+            assert_eq!(hand_type, hand.hand_type);
+            (
+                cards.into_iter().map(char::from).collect::<String>(),
+                hand_type.to_string(),
+            )
+        };
+        assert_eq!(
+            substitute("KTJJT"),
+            ("KTTTT".into(), "four of a kind".into())
+        );
+        assert_eq!(
+            substitute("QJJQ2"),
+            ("QQQQ2".into(), "four of a kind".into())
+        );
+        assert_eq!(
+            substitute("2J3J4"),
+            ("24344".into(), "three of a kind".into())
+        );
+        assert_eq!(
+            substitute("JJJJJ"),
+            ("AAAAA".into(), "five of a kind".into())
+        );
+        assert_eq!(substitute("32T3K"), ("32T3K".into(), "one pair".into()));
+    }
+
+    #[test]
+    fn explained_listing() {
+        let listing = explain_winnings(INPUT, &RuleSet::jokers());
+        assert_eq!(
+            listing.lines().next(),
+            Some("    1: 32T3K -> 32T3K one pair          765 * 1 = 765")
+        );
+        assert!(listing.contains("    5: KTJJT -> KTTTT four of a kind    220 * 5 = 1100"));
+        assert_eq!(listing.lines().last(), Some("total: 5905"));
+    }
+
+    #[test]
+    fn win_chance_exact() {
+        let standard = RuleSet::standard();
+        let jokers = RuleSet::jokers();
+        let a = Hand::parse("KK677", &standard).unwrap();
+        let known = win_chance(&a, "KTJJT", &standard).unwrap();
+        assert_eq!((known.wins, known.ties, known.trials), (1, 0, 1));
+        let same = win_chance(&a, "KK677", &standard).unwrap();
+        assert_eq!((same.wins, same.ties, same.trials), (0, 1, 1));
+        // KTJJ? only beats KK677 by turning into three Js
+        let one = win_chance(&a, "KTJJ?", &standard).unwrap();
+        assert!(one.exact);
+        assert_eq!(one.trials, 13);
+        assert_eq!(one.wins, 12);
+        let one_joker =
+            win_chance(&Hand::parse("KK677", &jokers).unwrap(), "KTJJ?", &jokers).unwrap();
+        assert_eq!(one_joker.wins, 0);
+        // KK6?? loses as one pair (110), as two pair KK66x (11), KK6x6 or KK6xx with x below 7
+        // (4 + 4) or KK676 (1), and only ties as KK677 itself
+        let two = win_chance(&a, "KK6??", &standard).unwrap();
+        assert_eq!(two.trials, 169);
+        assert_eq!((two.wins, two.ties), (130, 1));
+        assert!(win_chance(&a, "KK6?", &standard).is_err());
+    }
+
+    #[test]
+    fn win_chance_monte_carlo() {
+        let rules = RuleSet::standard();
+        let a = Hand::parse("2345678", &rules).unwrap();
+        let chance = win_chance(&a, "???????", &rules).unwrap();
+        assert!(!chance.exact);
+        assert_eq!(chance.trials, MONTE_CARLO_SAMPLES);
+        // only all distinct hands weaker than 2345678 lose to it
+        assert!(chance.probability() < 0.01);
+        let five = win_chance(&Hand::parse("AAAAA", &rules).unwrap(), "?????", &rules).unwrap();
+        assert!(five.exact);
+        assert_eq!(five.trials, 13u64.pow(5));
+        assert_eq!((five.wins, five.ties), (13u64.pow(5) - 1, 1));
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter, Write};
 use std::ops::{ControlFlow, Index};
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -34,26 +34,34 @@ impl Display for Direction {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-struct Node([u8; 3]);
+struct Node<'a>(&'a str);
 
-impl Display for Node {
+impl<'a> Node<'a> {
+    fn parse(s: &'a str) -> Result<Self, &'static str> {
+        if s.is_empty() {
+            Err("node name should not be empty")
+        } else if s.chars().any(|x| x.is_whitespace() || "=(),".contains(x)) {
+            Err("node name should not have whitespace or punctuation")
+        } else {
+            Ok(Self(s))
+        }
+    }
+}
+
+impl Display for Node<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}{}{}",
-            self.0[0] as char, self.0[1] as char, self.0[2] as char
-        )
+        f.write_str(self.0)
     }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
-struct Fork {
-    left: Node,
-    right: Node,
+struct Fork<'a> {
+    left: Node<'a>,
+    right: Node<'a>,
 }
 
-impl Index<Direction> for Fork {
-    type Output = Node;
+impl<'a> Index<Direction> for Fork<'a> {
+    type Output = Node<'a>;
     fn index(&self, index: Direction) -> &Self::Output {
         match index {
             Direction::Left => &self.left,
@@ -62,106 +70,354 @@ impl Index<Direction> for Fork {
     }
 }
 
-impl Display for Fork {
+impl Display for Fork<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}, {})", self.left, self.right)
     }
 }
 
-const START: Node = Node(*b"AAA");
-const FINISH: Node = Node(*b"ZZZ");
+const START: Node = Node("AAA");
+const FINISH: Node = Node("ZZZ");
 
-#[aoc(day8, part1)]
-fn part1(input: &str) -> usize {
-    let (dirs_str, nodes_str) = input.split_once("\n\n").expect("should have newline");
-    let nodes: HashMap<Node, Fork> = nodes_str
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum ParseError {
+    MissingDirections,
+    InvalidDirection { line: usize, found: char },
+    InvalidNodeLine { line: usize, reason: &'static str },
+    DuplicateNode { line: usize, node: String },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::MissingDirections => write!(f, "missing directions line"),
+            ParseError::InvalidDirection { line, found } => {
+                write!(f, "line {line}: invalid direction {found:?}")
+            }
+            ParseError::InvalidNodeLine { line, reason } => write!(f, "line {line}: {reason}"),
+            ParseError::DuplicateNode { line, node } => {
+                write!(f, "line {line}: node {node} defined twice")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Expects a node and its fork like "AAA = (BBB, CCC)", spacing optional
+fn parse_node_line(line: &str) -> Result<(Node<'_>, Fork<'_>), &'static str> {
+    let (name, fork) = line.split_once('=').ok_or("node line should have '='")?;
+    let (left, right) = fork
+        .trim()
+        .strip_prefix('(')
+        .and_then(|x| x.strip_suffix(')'))
+        .ok_or("fork should be in parentheses")?
+        .split_once(',')
+        .ok_or("fork should have two nodes")?;
+    Ok((
+        Node::parse(name.trim())?,
+        Fork {
+            left: Node::parse(left.trim())?,
+            right: Node::parse(right.trim())?,
+        },
+    ))
+}
+
+type NodeLine<'a> = (usize, Node<'a>, Fork<'a>);
+
+/// The directions, then a lazy parser over the numbered node lines so callers can stop early
+fn split_network(
+    input: &str,
+) -> Result<
+    (
+        Vec<Direction>,
+        impl Iterator<Item = Result<NodeLine<'_>, ParseError>>,
+    ),
+    ParseError,
+> {
+    let mut lines = input
         .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+    let (dirs_line, dirs_str) = lines.next().ok_or(ParseError::MissingDirections)?;
+    let dirs = dirs_str
+        .chars()
         .map(|x| {
-            let [k0, k1, k2, b' ', b'=', b' ', b'(', v00, v01, v02, b',', b' ', v10,v11,v12, b')'] = *x.as_bytes() else {
-                panic!("should've matched node pattern")
-            };
-            (Node([k0, k1, k2]), Fork{left: Node([v00, v01, v02]), right: Node([v10, v11, v12])})
+            Direction::try_from(x).map_err(|()| ParseError::InvalidDirection {
+                line: dirs_line,
+                found: x,
+            })
         })
-        .collect();
-    dirs_str
-        .chars()
-        .map(Direction::try_from)
-        .map(|x| x.expect("should be valid direction"))
-        .cycle()
-        .scan(nodes[&START], |curr, dir| match curr[dir] {
-            FINISH => None,
-            ref x => {
-                *curr = nodes[x];
-                Some(())
-            }
+        .collect::<Result<_, _>>()?;
+    let nodes = lines.map(|(line, x)| match parse_node_line(x) {
+        Ok((node, fork)) => Ok((line, node, fork)),
+        Err(reason) => Err(ParseError::InvalidNodeLine { line, reason }),
+    });
+    Ok((dirs, nodes))
+}
+
+/// Anything a walker can step through, so the same walks run on the parsed map or on the
+/// compiled arrays
+trait Network<'a>: Default {
+    type Id: Copy + Eq + std::hash::Hash;
+    fn insert(&mut self, node: Node<'a>, fork: Fork<'a>);
+    /// `None` if the network has never heard of the node
+    fn id(&self, node: Node<'a>) -> Option<Self::Id>;
+    fn node(&self, id: Self::Id) -> Node<'a>;
+    /// Whether the node's own fork has been inserted rather than just pointed at
+    fn is_defined(&self, id: Self::Id) -> bool;
+    fn step(&self, id: Self::Id, dir: Direction) -> Self::Id;
+    fn ids(&self) -> impl Iterator<Item = Self::Id> + '_;
+    /// `predicate` worked out up front where the ids allow it, for checking on every step
+    fn mask(&self, predicate: impl Fn(Node<'a>) -> bool) -> impl Fn(Self::Id) -> bool;
+}
+
+impl<'a> Network<'a> for HashMap<Node<'a>, Fork<'a>> {
+    type Id = Node<'a>;
+
+    fn insert(&mut self, node: Node<'a>, fork: Fork<'a>) {
+        HashMap::insert(self, node, fork);
+    }
+
+    fn id(&self, node: Node<'a>) -> Option<Self::Id> {
+        self.contains_key(&node).then_some(node)
+    }
+
+    fn node(&self, id: Self::Id) -> Node<'a> {
+        id
+    }
+
+    fn is_defined(&self, id: Self::Id) -> bool {
+        self.contains_key(&id)
+    }
+
+    fn step(&self, id: Self::Id, dir: Direction) -> Self::Id {
+        self[&id][dir]
+    }
+
+    fn ids(&self) -> impl Iterator<Item = Self::Id> + '_ {
+        self.keys().copied()
+    }
+
+    fn mask(&self, predicate: impl Fn(Node<'a>) -> bool) -> impl Fn(Self::Id) -> bool {
+        predicate
+    }
+}
+
+/// Nodes interned to dense indices with their forks in one flat array, so a step is just indexing
+#[derive(Debug, Clone, Default)]
+struct CompiledNetwork<'a> {
+    names: Vec<Node<'a>>,
+    ids: HashMap<Node<'a>, u32>,
+    // left then right, UNDEFINED until the node's own line has been seen
+    forks: Vec<[u32; 2]>,
+}
+
+impl<'a> CompiledNetwork<'a> {
+    const UNDEFINED: u32 = u32::MAX;
+
+    fn intern(&mut self, node: Node<'a>) -> u32 {
+        *self.ids.entry(node).or_insert_with(|| {
+            self.names.push(node);
+            self.forks.push([Self::UNDEFINED; 2]);
+            (self.names.len() - 1)
+                .try_into()
+                .expect("should have fewer nodes than u32::MAX")
         })
-        .count()
-        + 1
+    }
+}
+
+impl<'a> Network<'a> for CompiledNetwork<'a> {
+    type Id = u32;
+
+    fn insert(&mut self, node: Node<'a>, fork: Fork<'a>) {
+        let id = self.intern(node);
+        let left = self.intern(fork.left);
+        let right = self.intern(fork.right);
+        self.forks[id as usize] = [left, right];
+    }
+
+    fn id(&self, node: Node<'a>) -> Option<Self::Id> {
+        self.ids.get(&node).copied()
+    }
+
+    fn node(&self, id: Self::Id) -> Node<'a> {
+        self.names[id as usize]
+    }
+
+    fn is_defined(&self, id: Self::Id) -> bool {
+        self.forks[id as usize][0] != Self::UNDEFINED
+    }
+
+    fn step(&self, id: Self::Id, dir: Direction) -> Self::Id {
+        self.forks[id as usize][dir as usize]
+    }
+
+    fn ids(&self) -> impl Iterator<Item = Self::Id> + '_ {
+        (0..self.names.len() as u32).filter(|&id| self.is_defined(id))
+    }
+
+    fn mask(&self, predicate: impl Fn(Node<'a>) -> bool) -> impl Fn(Self::Id) -> bool {
+        let mask: Vec<bool> = self.names.iter().map(|&x| predicate(x)).collect();
+        move |id| mask[id as usize]
+    }
+}
+
+fn parse_network<'a, N: Network<'a>>(input: &'a str) -> Result<(Vec<Direction>, N), ParseError> {
+    let (dirs, node_parser) = split_network(input)?;
+    let mut nodes = N::default();
+    for parsed in node_parser {
+        let (line, node, fork) = parsed?;
+        if nodes.id(node).is_some_and(|id| nodes.is_defined(id)) {
+            return Err(ParseError::DuplicateNode {
+                line,
+                node: node.to_string(),
+            });
+        }
+        nodes.insert(node, fork);
+    }
+    Ok((dirs, nodes))
+}
+
+/// Which nodes a walk starts from or stops at
+#[derive(Debug, Clone)]
+enum NodePredicate<'p> {
+    Exact(&'p str),
+    Suffix(&'p str),
+    Prefix(&'p str),
+    Regex(regex::Regex),
+    Set(HashSet<&'p str>),
+}
+
+impl NodePredicate<'_> {
+    fn matches(&self, Node(name): Node) -> bool {
+        match self {
+            NodePredicate::Exact(x) => name == *x,
+            NodePredicate::Suffix(x) => name.ends_with(x),
+            NodePredicate::Prefix(x) => name.starts_with(x),
+            NodePredicate::Regex(x) => x.is_match(name),
+            NodePredicate::Set(x) => x.contains(name),
+        }
+    }
+}
+
+const GHOST_STARTS: NodePredicate = NodePredicate::Suffix("A");
+const GHOST_GOALS: NodePredicate = NodePredicate::Suffix("Z");
+
+/// For every start, the first step (at least one) that lands on a goal, or `None` if it never does
+fn first_goals<'a>(
+    dirs: &[Direction],
+    nodes: &impl Network<'a>,
+    starts: &NodePredicate,
+    goals: &NodePredicate,
+) -> Vec<(Node<'a>, Option<usize>)> {
+    // past this many steps a walker has been in some (node, offset) state twice, so it's looping
+    let n_states = nodes.ids().count() * dirs.len();
+    let is_goal = nodes.mask(|node| goals.matches(node));
+    let mut found: Vec<_> = nodes
+        .ids()
+        .filter(|&id| starts.matches(nodes.node(id)))
+        .map(|start| {
+            let mut curr = start;
+            let steps = dirs
+                .iter()
+                .cycle()
+                .take(n_states)
+                .position(|&dir| {
+                    curr = nodes.step(curr, dir);
+                    is_goal(curr)
+                })
+                .map(|x| x + 1);
+            (nodes.node(start), steps)
+        })
+        .collect();
+    found.sort_unstable_by_key(|&(Node(x), _)| x);
+    found
+}
+
+fn steps_to_finish<'a>(dirs: &[Direction], nodes: &impl Network<'a>) -> usize {
+    let [(_, steps)] = first_goals(
+        dirs,
+        nodes,
+        &NodePredicate::Exact(START.0),
+        &NodePredicate::Exact(FINISH.0),
+    )[..] else {
+        panic!("should have exactly one start")
+    };
+    steps.expect("should reach finish")
+}
+
+#[aoc(day8, part1)]
+fn part1(input: &str) -> usize {
+    let (dirs, nodes): (_, HashMap<_, _>) = parse_network(input).expect("should be valid network");
+    steps_to_finish(&dirs, &nodes)
 }
 
-fn populate_find<'a, K: Eq + std::hash::Hash, V>(
-    search_key: &K,
-    iter: &mut impl Iterator<Item = (K, V)>,
-    cache: &'a mut HashMap<K, V>,
-) -> Option<&'a V> {
-    match iter.try_for_each(|(key, val)| {
-        let found = key.eq(search_key);
-        cache.insert(key, val);
-        if found {
+#[aoc(day8, part1, compiled)]
+fn part1_compiled(input: &str) -> usize {
+    let (dirs, nodes): (_, CompiledNetwork) =
+        parse_network(input).expect("should be valid network");
+    steps_to_finish(&dirs, &nodes)
+}
+
+fn populate_find<'a, N: Network<'a>>(
+    search: Node<'a>,
+    iter: &mut impl Iterator<Item = (Node<'a>, Fork<'a>)>,
+    cache: &mut N,
+) -> Option<N::Id> {
+    match iter.try_for_each(|(node, fork)| {
+        cache.insert(node, fork);
+        if node == search {
             ControlFlow::Break(())
         } else {
             ControlFlow::Continue(())
         }
     }) {
         ControlFlow::Continue(()) => None,
-        ControlFlow::Break(()) => Some(
-            cache
-                .get(search_key)
-                .expect("should've made sure it was in"),
-        ),
+        ControlFlow::Break(()) => Some(cache.id(search).expect("should've made sure it was in")),
     }
 }
 
-#[aoc(day8, part1, caching)]
-fn part1_caching(input: &str) -> usize {
-    let (dirs_str, nodes_str) = input.split_once("\n\n").expect("should have newline");
-    let mut node_parser = nodes_str.lines().map(|x| {
-        let [k0, k1, k2, b' ', b'=', b' ', b'(', v00, v01, v02, b',', b' ', v10, v11, v12, b')'] =
-            *x.as_bytes()
-        else {
-            panic!("should've matched node pattern")
-        };
-        (
-            Node([k0, k1, k2]),
-            Fork {
-                left: Node([v00, v01, v02]),
-                right: Node([v10, v11, v12]),
-            },
-        )
+fn steps_to_finish_caching<'a, N: Network<'a>>(input: &'a str) -> usize {
+    let (dirs, node_parser) = split_network(input).expect("should be valid directions");
+    let mut node_parser = node_parser.map(|x| {
+        let (_, node, fork) = x.expect("should be valid node line");
+        (node, fork)
     });
-    let mut nodes = HashMap::new();
-    let start_fork =
-        *populate_find(&START, &mut node_parser, &mut nodes).expect("should have start");
-    dirs_str
-        .chars()
-        .map(Direction::try_from)
-        .map(|x| x.expect("should be valid direction"))
+    let mut nodes = N::default();
+    let start = populate_find(START, &mut node_parser, &mut nodes).expect("should have start");
+    dirs.into_iter()
         .cycle()
-        .scan(start_fork, |curr, dir| match curr[dir] {
-            FINISH => None,
-            ref next => {
-                *curr = match nodes.get(next) {
-                    Some(&x) => x,
-                    None => *populate_find(next, &mut node_parser, &mut nodes)
-                        .expect("should have next"),
-                };
-                Some(())
+        .scan(start, |curr, dir| {
+            let next = nodes.step(*curr, dir);
+            match nodes.node(next) {
+                FINISH => None,
+                next_node => {
+                    *curr = if nodes.is_defined(next) {
+                        next
+                    } else {
+                        populate_find(next_node, &mut node_parser, &mut nodes)
+                            .expect("should have next")
+                    };
+                    Some(())
+                }
             }
         })
         .count()
         + 1
 }
 
+#[aoc(day8, part1, caching)]
+fn part1_caching(input: &str) -> usize {
+    steps_to_finish_caching::<HashMap<_, _>>(input)
+}
+
+#[aoc(day8, part1, caching_compiled)]
+fn part1_caching_compiled(input: &str) -> usize {
+    steps_to_finish_caching::<CompiledNetwork>(input)
+}
+
 fn gcd(a: usize, b: usize) -> usize {
     if b == 0 {
         a
@@ -174,51 +430,523 @@ fn lcm(a: usize, b: usize) -> usize {
     a * (b / gcd(a, b))
 }
 
+fn ghost_steps<'a>(dirs: &[Direction], nodes: &impl Network<'a>) -> usize {
+    first_goals(dirs, nodes, &GHOST_STARTS, &GHOST_GOALS)
+        .into_iter()
+        .map(|(_, steps)| steps.expect("every ghost should reach a goal"))
+        .fold(1, lcm)
+}
+
 #[aoc(day8, part2)]
 fn part2(input: &str) -> usize {
-    let (dirs_str, nodes_str) = input.split_once("\n\n").expect("should have newline");
-    let nodes: HashMap<Node, Fork> = nodes_str
-        .lines()
-        .map(|x| {
-            let [k0, k1, k2, b' ', b'=', b' ', b'(', v00, v01, v02, b',', b' ', v10,v11,v12, b')'] = *x.as_bytes() else {
-                panic!("should've matched node pattern")
-            };
-            (Node([k0, k1, k2]), Fork{left: Node([v00, v01, v02]), right: Node([v10, v11, v12])})
-        })
-        .collect();
-    let mut forks: Vec<Fork> = nodes
-        .iter()
-        .filter(|(&Node([_, _, x]), _)| x == b'A')
-        // .inspect(|x| println!("{}: {}", x.0, x.1))
-        .map(|(_, &x)| x)
-        .collect();
-    let mut full_count = 1usize;
-    for (i, dir) in dirs_str
-        .chars()
-        .map(Direction::try_from)
-        .map(|x| x.expect("should be valid direction"))
-        .cycle()
-        .enumerate()
-    {
-        let mut any_finished: bool = false;
-        forks = forks
+    let (dirs, nodes): (_, HashMap<_, _>) = parse_network(input).expect("should be valid network");
+    ghost_steps(&dirs, &nodes)
+}
+
+#[aoc(day8, part2, compiled)]
+fn part2_compiled(input: &str) -> usize {
+    let (dirs, nodes): (_, CompiledNetwork) =
+        parse_network(input).expect("should be valid network");
+    ghost_steps(&dirs, &nodes)
+}
+
+/// Every step a ghost stands on a goal, split into one-off hits from before its walk settles into
+/// a cycle and hits that come back every `cycle_len` steps after that
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct GhostCycle {
+    cycle_start: usize,
+    cycle_len: usize,
+    pre_cycle_hits: Vec<usize>,
+    cycle_hits: Vec<usize>,
+}
+
+impl GhostCycle {
+    fn new(
+        start: Node,
+        dirs: &[Direction],
+        nodes: &HashMap<Node, Fork>,
+        is_goal: impl Fn(Node) -> bool,
+    ) -> Self {
+        // a ghost's state is where it is and how far through the instructions it is
+        let mut seen: HashMap<(Node, usize), usize> = HashMap::new();
+        let mut hits = Vec::new();
+        let mut node = start;
+        let mut step = 0;
+        let cycle_start = loop {
+            if let Some(&first_seen) = seen.get(&(node, step % dirs.len())) {
+                break first_seen;
+            }
+            seen.insert((node, step % dirs.len()), step);
+            if step > 0 && is_goal(node) {
+                hits.push(step);
+            }
+            node = nodes[&node][dirs[step % dirs.len()]];
+            step += 1;
+        };
+        let cycle_hits = hits.split_off(hits.partition_point(|&x| x < cycle_start));
+        Self {
+            cycle_start,
+            cycle_len: step - cycle_start,
+            pre_cycle_hits: hits,
+            cycle_hits,
+        }
+    }
+
+    fn hits_at(&self, step: usize) -> bool {
+        if step < self.cycle_start {
+            self.pre_cycle_hits.contains(&step)
+        } else {
+            let offset = (step - self.cycle_start) % self.cycle_len;
+            self.cycle_hits
+                .iter()
+                .any(|&x| x - self.cycle_start == offset)
+        }
+    }
+}
+
+fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = ext_gcd(b, a % b);
+        (g, y, x - a / b * y)
+    }
+}
+
+/// Merges `t = r1 (mod m1)` and `t = r2 (mod m2)` into one congruence, if both can hold at once
+fn crt((r1, m1): (i128, i128), (r2, m2): (i128, i128)) -> Option<(i128, i128)> {
+    let (g, p, _) = ext_gcd(m1, m2);
+    if (r2 - r1) % g != 0 {
+        return None;
+    }
+    let m2_g = m2 / g;
+    let t = ((r2 - r1) / g % m2_g * p).rem_euclid(m2_g);
+    let lcm = m1 * m2_g;
+    Some(((r1 + m1 * t).rem_euclid(lcm), lcm))
+}
+
+/// First step where every ghost is on a goal at once
+fn first_common_hit(cycles: &[GhostCycle]) -> Option<usize> {
+    let settled = cycles.iter().map(|x| x.cycle_start).max()?;
+    // until every ghost is in its cycle, just check each step
+    if let Some(step) = (1..settled).find(|&step| cycles.iter().all(|x| x.hits_at(step))) {
+        return Some(step);
+    }
+    // after that each ghost allows a few residues, so try every combination of them
+    let mut congruences = vec![(0i128, 1i128)];
+    for cycle in cycles {
+        let len = cycle.cycle_len as i128;
+        congruences = congruences
             .iter()
-            .filter_map(|fork| match fork[dir] {
-                Node([_, _, b'Z']) => {
-                    any_finished = true;
-                    None
+            .flat_map(|&congruence| {
+                cycle
+                    .cycle_hits
+                    .iter()
+                    .filter_map(move |&hit| crt(congruence, (hit as i128 % len, len)))
+            })
+            .collect();
+    }
+    let settled = settled.max(1) as i128;
+    congruences
+        .into_iter()
+        .map(|(r, m)| r + ((settled - r).max(0) + m - 1) / m * m)
+        .min()
+        .map(|step| step.try_into().expect("should fit in usize"))
+}
+
+#[aoc(day8, part2, crt)]
+fn part2_crt(input: &str) -> anyhow::Result<usize> {
+    let (dirs, nodes): (_, HashMap<_, _>) = parse_network(input)?;
+    let cycles: Vec<GhostCycle> = nodes
+        .keys()
+        .filter(|&&x| GHOST_STARTS.matches(x))
+        .map(|&start| GhostCycle::new(start, &dirs, &nodes, |x| GHOST_GOALS.matches(x)))
+        .collect();
+    first_common_hit(&cycles)
+        .ok_or_else(|| anyhow::anyhow!("ghosts are never all on Z nodes at the same time"))
+}
+
+/// Binary lifting over (node, instruction offset) states, so where a walker is after a huge number
+/// of steps, or when it next lands on a goal, takes a logarithmic number of lookups
+struct JumpTable<'n, 'a> {
+    nodes: &'n CompiledNetwork<'a>,
+    dirs_len: usize,
+    // jumps[k][state] is the state 2^k steps after state
+    jumps: Vec<Vec<u32>>,
+    // goal_within[k][state] is whether any of the next 2^k steps lands on a goal
+    goal_within: Vec<Vec<bool>>,
+    is_goal: Vec<bool>,
+}
+
+impl<'n, 'a> JumpTable<'n, 'a> {
+    /// Enough levels to answer any `n` up to `max_steps`, and always enough to outlast every cycle.
+    /// Nodes that are only ever pointed at are terminal, a walker that gets there stays there
+    fn new(
+        dirs: &[Direction],
+        nodes: &'n CompiledNetwork<'a>,
+        max_steps: u64,
+        is_goal: impl Fn(Node) -> bool,
+    ) -> Self {
+        let n_states = nodes.names.len() * dirs.len();
+        let levels = (64 - max_steps.leading_zeros())
+            .max(usize::BITS - n_states.leading_zeros() + 1)
+            .min(63) as usize;
+        let is_goal: Vec<bool> = nodes.names.iter().map(|&x| is_goal(x)).collect();
+        let first: Vec<u32> = (0..n_states)
+            .map(|state| {
+                let (id, offset) = (state / dirs.len(), state % dirs.len());
+                if !nodes.is_defined(id as u32) {
+                    return state as u32;
                 }
-                ref x => Some(nodes[x]),
+                let next = nodes.step(id as u32, dirs[offset]) as usize;
+                (next * dirs.len() + (offset + 1) % dirs.len()) as u32
             })
             .collect();
-        if any_finished {
-            full_count = lcm(full_count, i + 1);
-            if forks.is_empty() {
-                break;
+        let first_goal: Vec<bool> = first
+            .iter()
+            .map(|&state| is_goal[state as usize / dirs.len()])
+            .collect();
+        let mut jumps = vec![first];
+        let mut goal_within = vec![first_goal];
+        for k in 1..levels {
+            let (prev, prev_goal) = (&jumps[k - 1], &goal_within[k - 1]);
+            let next: Vec<u32> = prev.iter().map(|&mid| prev[mid as usize]).collect();
+            let next_goal: Vec<bool> = (0..n_states)
+                .map(|state| prev_goal[state] || prev_goal[prev[state] as usize])
+                .collect();
+            jumps.push(next);
+            goal_within.push(next_goal);
+        }
+        Self {
+            nodes,
+            dirs_len: dirs.len(),
+            jumps,
+            goal_within,
+            is_goal,
+        }
+    }
+
+    fn start_state(&self, start: Node<'a>) -> u32 {
+        let id = self.nodes.id(start).expect("should have start");
+        (id as usize * self.dirs_len) as u32
+    }
+
+    fn advance(&self, mut state: u32, n: u64) -> u32 {
+        assert!(
+            n < 1 << self.jumps.len(),
+            "table only goes up to 2^{} steps",
+            self.jumps.len()
+        );
+        for (k, jump) in self.jumps.iter().enumerate() {
+            if n >> k & 1 == 1 {
+                state = jump[state as usize];
+            }
+        }
+        state
+    }
+
+    /// The node and instruction offset a walker from `start` is at after `n` steps
+    fn state_after(&self, start: Node<'a>, n: u64) -> (Node<'a>, usize) {
+        let state = self.advance(self.start_state(start), n) as usize;
+        (
+            self.nodes.node((state / self.dirs_len) as u32),
+            state % self.dirs_len,
+        )
+    }
+
+    /// The first step at or after `n` where a walker from `start` is on a goal, if it ever is
+    fn first_goal_from(&self, start: Node<'a>, n: u64) -> Option<u64> {
+        let mut state = self.advance(self.start_state(start), n);
+        if self.is_goal[state as usize / self.dirs_len] {
+            return Some(n);
+        }
+        // jump as far as possible without passing a goal, then the goal is one step away
+        let mut skipped = 0u64;
+        for (k, (jump, goal_within)) in self.jumps.iter().zip(&self.goal_within).enumerate().rev() {
+            if !goal_within[state as usize] {
+                state = jump[state as usize];
+                skipped += 1 << k;
             }
         }
+        if self.goal_within[0][state as usize] {
+            n.checked_add(skipped + 1)
+        } else {
+            None
+        }
     }
-    full_count
+}
+
+#[aoc(day8, part1, jumps)]
+fn part1_jumps(input: &str) -> usize {
+    let (dirs, nodes): (_, CompiledNetwork) =
+        parse_network(input).expect("should be valid network");
+    let table = JumpTable::new(&dirs, &nodes, 1, |x| x == FINISH);
+    table
+        .first_goal_from(START, 1)
+        .expect("should reach finish") as usize
+}
+
+/// The defined nodes a node's fork leads to, once each
+fn successors<'a, N: Network<'a>>(nodes: &N, id: N::Id) -> Vec<N::Id> {
+    let left = nodes.step(id, Direction::Left);
+    let right = nodes.step(id, Direction::Right);
+    std::iter::once(left)
+        .chain((right != left).then_some(right))
+        .filter(|&x| nodes.is_defined(x))
+        .collect()
+}
+
+/// Graphviz source for the network, with starts filled green and goals filled red
+fn to_dot<'a>(nodes: &impl Network<'a>, starts: &NodePredicate, goals: &NodePredicate) -> String {
+    let mut ids: Vec<_> = nodes.ids().collect();
+    ids.sort_unstable_by_key(|&id| nodes.node(id).0);
+    let mut out = String::from("digraph network {\n");
+    for &id in &ids {
+        let node = nodes.node(id);
+        let color = match (starts.matches(node), goals.matches(node)) {
+            (true, true) => Some("gold"),
+            (true, false) => Some("palegreen"),
+            (false, true) => Some("lightcoral"),
+            (false, false) => None,
+        };
+        if let Some(color) = color {
+            writeln!(out, "    \"{node}\" [style=filled, fillcolor={color}];")
+                .expect("should write to string");
+        }
+    }
+    for &id in &ids {
+        let node = nodes.node(id);
+        let left = nodes.node(nodes.step(id, Direction::Left));
+        let right = nodes.node(nodes.step(id, Direction::Right));
+        if left == right {
+            writeln!(out, "    \"{node}\" -> \"{left}\" [label=\"LR\"];")
+        } else {
+            writeln!(out, "    \"{node}\" -> \"{left}\" [label=\"L\"];")
+                .and_then(|()| writeln!(out, "    \"{node}\" -> \"{right}\" [label=\"R\"];"))
+        }
+        .expect("should write to string");
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct NetworkAnalysis<'a> {
+    /// Strongly connected components, biggest first
+    components: Vec<Vec<Node<'a>>>,
+    /// Nodes no start can ever get to, whatever the instructions
+    unreachable: Vec<Node<'a>>,
+    /// Nodes like `XXX = (XXX, XXX)` that trap anything walking into them
+    dead_ends: Vec<Node<'a>>,
+}
+
+impl<'a> NetworkAnalysis<'a> {
+    fn new<N: Network<'a>>(nodes: &N, starts: &NodePredicate) -> Self {
+        let mut components: Vec<Vec<Node>> = strongly_connected(nodes)
+            .into_iter()
+            .map(|component| {
+                let mut names: Vec<_> = component.into_iter().map(|id| nodes.node(id)).collect();
+                names.sort_unstable_by_key(|&Node(x)| x);
+                names
+            })
+            .collect();
+        components.sort_unstable_by(|a, b| b.len().cmp(&a.len()).then(a[0].0.cmp(b[0].0)));
+        let mut reached: HashSet<N::Id> = nodes
+            .ids()
+            .filter(|&id| starts.matches(nodes.node(id)))
+            .collect();
+        let mut stack: Vec<_> = reached.iter().copied().collect();
+        while let Some(id) = stack.pop() {
+            stack.extend(
+                successors(nodes, id)
+                    .into_iter()
+                    .filter(|&next| reached.insert(next)),
+            );
+        }
+        let mut unreachable: Vec<_> = nodes
+            .ids()
+            .filter(|id| !reached.contains(id))
+            .map(|id| nodes.node(id))
+            .collect();
+        unreachable.sort_unstable_by_key(|&Node(x)| x);
+        let mut dead_ends: Vec<_> = nodes
+            .ids()
+            .filter(|&id| {
+                nodes.step(id, Direction::Left) == id && nodes.step(id, Direction::Right) == id
+            })
+            .map(|id| nodes.node(id))
+            .collect();
+        dead_ends.sort_unstable_by_key(|&Node(x)| x);
+        Self {
+            components,
+            unreachable,
+            dead_ends,
+        }
+    }
+}
+
+impl Display for NetworkAnalysis<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let list = |nodes: &[Node]| {
+            if nodes.is_empty() {
+                String::new()
+            } else {
+                let names: Vec<_> = nodes.iter().map(ToString::to_string).collect();
+                format!(": {}", names.join(", "))
+            }
+        };
+        writeln!(
+            f,
+            "{} strongly connected components, sizes {:?}",
+            self.components.len(),
+            self.components.iter().map(Vec::len).collect::<Vec<_>>()
+        )?;
+        writeln!(
+            f,
+            "{} unreachable{}",
+            self.unreachable.len(),
+            list(&self.unreachable)
+        )?;
+        write!(
+            f,
+            "{} dead ends{}",
+            self.dead_ends.len(),
+            list(&self.dead_ends)
+        )
+    }
+}
+
+/// Tarjan's algorithm, iterative so long chains can't overflow the stack
+fn strongly_connected<'a, N: Network<'a>>(nodes: &N) -> Vec<Vec<N::Id>> {
+    let mut index: HashMap<N::Id, usize> = HashMap::new();
+    let mut low_link: HashMap<N::Id, usize> = HashMap::new();
+    let mut on_stack: HashSet<N::Id> = HashSet::new();
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    for root in nodes.ids() {
+        if index.contains_key(&root) {
+            continue;
+        }
+        let mut work = vec![(root, successors(nodes, root))];
+        index.insert(root, index.len());
+        low_link.insert(root, index[&root]);
+        stack.push(root);
+        on_stack.insert(root);
+        while let Some((id, pending)) = work.last_mut() {
+            let id = *id;
+            if let Some(next) = pending.pop() {
+                if !index.contains_key(&next) {
+                    index.insert(next, index.len());
+                    low_link.insert(next, index[&next]);
+                    stack.push(next);
+                    on_stack.insert(next);
+                    work.push((next, successors(nodes, next)));
+                } else if on_stack.contains(&next) {
+                    let low = low_link[&id].min(index[&next]);
+                    low_link.insert(id, low);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                let low = low_link[&parent].min(low_link[&id]);
+                low_link.insert(parent, low);
+            }
+            if low_link[&id] == index[&id] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(&member);
+                    component.push(member);
+                    if member == id {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+/// Strongly connected components, unreachable nodes and dead ends of the network, as text
+fn network_structure(input: &str) -> String {
+    let (_, nodes): (_, CompiledNetwork) = parse_network(input).expect("should be valid network");
+    NetworkAnalysis::new(&nodes, &GHOST_STARTS).to_string()
+}
+
+/// Fewest steps from `start` to a goal if the walker could pick L or R freely, as the
+/// instructions that get there. Empty if `start` is already a goal
+fn shortest_path<'a, N: Network<'a>>(
+    nodes: &N,
+    start: Node<'a>,
+    goals: &NodePredicate,
+) -> Option<Vec<Direction>> {
+    let start_id = nodes.id(start)?;
+    if goals.matches(start) {
+        return Some(Vec::new());
+    }
+    let mut parents: HashMap<N::Id, (N::Id, Direction)> = HashMap::new();
+    let mut queue = VecDeque::from([start_id]);
+    while let Some(id) = queue.pop_front() {
+        for dir in [Direction::Left, Direction::Right] {
+            let next = nodes.step(id, dir);
+            if next == start_id || parents.contains_key(&next) {
+                continue;
+            }
+            parents.insert(next, (id, dir));
+            if goals.matches(nodes.node(next)) {
+                let mut path = Vec::new();
+                let mut curr = next;
+                while curr != start_id {
+                    let (prev, dir) = parents[&curr];
+                    path.push(dir);
+                    curr = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if nodes.is_defined(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Free-choice step counts between every pair of nodes where one can reach the other
+fn all_distances<'a, N: Network<'a>>(nodes: &N) -> HashMap<(Node<'a>, Node<'a>), usize> {
+    let mut distances = HashMap::new();
+    for start in nodes.ids() {
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((id, dist)) = queue.pop_front() {
+            distances.insert((nodes.node(start), nodes.node(id)), dist);
+            if !nodes.is_defined(id) {
+                continue;
+            }
+            for dir in [Direction::Left, Direction::Right] {
+                let next = nodes.step(id, dir);
+                if seen.insert(next) {
+                    queue.push_back((next, dist + 1));
+                }
+            }
+        }
+    }
+    distances
+}
+
+/// The free-choice shortest route from AAA to ZZZ next to the steps the instructions take
+fn free_route(input: &str) -> String {
+    let (dirs, nodes): (_, CompiledNetwork) =
+        parse_network(input).expect("should be valid network");
+    let path = shortest_path(&nodes, START, &NodePredicate::Exact(FINISH.0))
+        .expect("should be able to reach finish");
+    let path_str: String = path.iter().map(|&x| char::from(x)).collect();
+    format!(
+        "{} ({path_str}), {} following the instructions",
+        path.len(),
+        steps_to_finish(&dirs, &nodes)
+    )
 }
 
 #[cfg(test)]
@@ -256,4 +984,276 @@ XXX = (XXX, XXX)";
     fn part2_example() {
         assert_eq!(part2(INPUT_2), 6);
     }
+
+    #[test]
+    fn compiled_matches_map() {
+        assert_eq!(part1_compiled(INPUT_1), part1(INPUT_1));
+        assert_eq!(part1_caching_compiled(INPUT_1), part1_caching(INPUT_1));
+        assert_eq!(part2_compiled(INPUT_2), part2(INPUT_2));
+        let (_, nodes): (_, CompiledNetwork) = parse_network(INPUT_2).unwrap();
+        assert_eq!(nodes.ids().count(), 8);
+        let a = nodes.id(Node("11A")).unwrap();
+        assert_eq!(nodes.node(nodes.step(a, Direction::Right)), Node("XXX"));
+    }
+
+    #[test]
+    fn part2_crt_example() {
+        assert_eq!(part2_crt(INPUT_2).unwrap(), 6);
+    }
+
+    #[test]
+    fn part2_crt_offsets() {
+        // 11 hits Z on steps 2 mod 3 and 22 on odd steps, so LCM of first hits would say 2
+        const INPUT: &str = "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22Z, 22Z)";
+        assert_eq!(part2_crt(INPUT).unwrap(), 5);
+        // 44 only ever hits Z once before getting stuck
+        const INPUT_ONCE: &str = "L
+
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22Z, 22Z)
+44A = (44Z, 44Z)
+44Z = (44X, 44X)
+44X = (44X, 44X)";
+        assert_eq!(part2_crt(INPUT_ONCE).unwrap(), 1);
+    }
+
+    #[test]
+    fn part2_crt_no_common_step() {
+        // 22 hits on odd steps and 33 on even ones
+        const INPUT: &str = "L
+
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22Z, 22Z)
+33A = (33B, 33B)
+33B = (33Z, 33Z)
+33Z = (33B, 33B)";
+        assert!(part2_crt(INPUT).is_err());
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((2, 3), (1, 2)), Some((5, 6)));
+        assert_eq!(crt((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(crt((0, 4), (1, 6)), None);
+    }
+
+    #[test]
+    fn long_names_and_spacing() {
+        const INPUT: &str = "LR
+
+START=(MIDDLE,  X)
+MIDDLE = ( X , FINISH )
+
+X = (X, X)
+FINISH = (FINISH, FINISH)";
+        let (dirs, nodes): (_, HashMap<_, _>) = parse_network(INPUT).unwrap();
+        assert_eq!(dirs, [Direction::Left, Direction::Right]);
+        assert_eq!(
+            nodes[&Node("MIDDLE")],
+            Fork {
+                left: Node("X"),
+                right: Node("FINISH")
+            }
+        );
+        let renamed = INPUT.replace("START", "AAA").replace("FINISH", "ZZZ");
+        assert_eq!(part1(&renamed), 2);
+        assert_eq!(part1_caching(&renamed), 2);
+        assert_eq!(part1_compiled(&renamed), 2);
+        assert_eq!(part1_caching_compiled(&renamed), 2);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse_network::<HashMap<_, _>>(""),
+            Err(ParseError::MissingDirections)
+        );
+        assert_eq!(
+            parse_network::<HashMap<_, _>>("LRX\n\nAAA = (AAA, AAA)"),
+            Err(ParseError::InvalidDirection {
+                line: 1,
+                found: 'X'
+            })
+        );
+        assert!(matches!(
+            parse_network::<HashMap<_, _>>("LR\n\nAAA = (AAA, AAA)\nBBB = (AAA AAA)"),
+            Err(ParseError::InvalidNodeLine { line: 4, .. })
+        ));
+        assert!(matches!(
+            parse_network::<HashMap<_, _>>("LR\n\nAAA = (AAA, )"),
+            Err(ParseError::InvalidNodeLine { line: 3, .. })
+        ));
+        assert_eq!(
+            parse_network::<HashMap<_, _>>("LR\n\nAAA = (AAA, AAA)\n\nAAA = (BBB, BBB)")
+                .unwrap_err()
+                .to_string(),
+            "line 5: node AAA defined twice"
+        );
+    }
+
+    #[test]
+    fn jump_table() {
+        let (dirs, nodes): (_, CompiledNetwork) = parse_network(INPUT_2).unwrap();
+        let table = JumpTable::new(&dirs, &nodes, 10u64.pow(15), |Node(x)| x.ends_with('Z'));
+        // 22A walks 22B 22C 22Z then repeats those three forever
+        assert_eq!(table.state_after(Node("22A"), 0), (Node("22A"), 0));
+        assert_eq!(table.state_after(Node("22A"), 3), (Node("22Z"), 1));
+        assert_eq!(
+            table.state_after(Node("22A"), 10u64.pow(15)),
+            (Node("22B"), 0)
+        );
+        assert_eq!(
+            table.state_after(Node("22A"), 10u64.pow(15) + 2),
+            (Node("22Z"), 0)
+        );
+        assert_eq!(table.first_goal_from(Node("22A"), 0), Some(3));
+        assert_eq!(table.first_goal_from(Node("22A"), 4), Some(6));
+        assert_eq!(table.first_goal_from(Node("22A"), 6), Some(6));
+        assert_eq!(
+            table.first_goal_from(Node("22A"), 10u64.pow(15)),
+            Some(10u64.pow(15) + 2)
+        );
+        // XXX only ever goes back to itself
+        assert_eq!(table.first_goal_from(Node("XXX"), 0), None);
+        assert_eq!(part1_jumps(INPUT_1), 6);
+    }
+
+    #[test]
+    fn jump_table_undefined_nodes() {
+        const INPUT: &str = "LR
+
+AAA = (ZZZ, QQQ)
+ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(part1(INPUT), 1);
+        assert_eq!(part1_jumps(INPUT), 1);
+        // going R first, the walker is stuck on QQQ as it has no fork
+        let rl_input = INPUT.replacen("LR", "RL", 1);
+        let (dirs, nodes): (_, CompiledNetwork) = parse_network(&rl_input).unwrap();
+        let table = JumpTable::new(&dirs, &nodes, 1 << 20, |x| x == FINISH);
+        assert_eq!(table.state_after(START, 1), (Node("QQQ"), 1));
+        assert_eq!(table.state_after(START, 1 << 20), (Node("QQQ"), 1));
+        assert_eq!(table.first_goal_from(START, 1), None);
+    }
+
+    #[test]
+    fn custom_predicates() {
+        const INPUT: &str = "RL
+
+ONEQ = (XONE, TWOQ)
+TWOQ = (XTWO, OTHER)
+XONE = (ONEQ, ONEQ)
+XTWO = (XTWO, XTWO)
+OTHER = (OTHER, OTHER)";
+        let (dirs, nodes): (_, CompiledNetwork) = parse_network(INPUT).unwrap();
+        let ends_in_q = NodePredicate::Suffix("Q");
+        let found = first_goals(&dirs, &nodes, &ends_in_q, &NodePredicate::Prefix("X"));
+        assert_eq!(found, [(Node("ONEQ"), Some(2)), (Node("TWOQ"), None)]);
+        let regex = NodePredicate::Regex(regex::Regex::new("^(ONE|TWO)Q$").unwrap());
+        let other = NodePredicate::Set(HashSet::from(["OTHER"]));
+        assert_eq!(
+            first_goals(&dirs, &nodes, &regex, &other),
+            [(Node("ONEQ"), None), (Node("TWOQ"), Some(1))]
+        );
+        assert_eq!(
+            first_goals(&dirs, &nodes, &NodePredicate::Exact("XTWO"), &ends_in_q),
+            [(Node("XTWO"), None)]
+        );
+        let is_goal = nodes.mask(|node| ends_in_q.matches(node));
+        for id in nodes.ids() {
+            assert_eq!(is_goal(id), ends_in_q.matches(nodes.node(id)));
+        }
+    }
+
+    #[test]
+    fn analysis() {
+        let (_, nodes): (_, CompiledNetwork) = parse_network(INPUT_2).unwrap();
+        let analysis = NetworkAnalysis::new(&nodes, &NodePredicate::Exact("22A"));
+        assert_eq!(
+            analysis.components,
+            [
+                vec![Node("22B"), Node("22C"), Node("22Z")],
+                vec![Node("11B"), Node("11Z")],
+                vec![Node("11A")],
+                vec![Node("22A")],
+                vec![Node("XXX")],
+            ]
+        );
+        assert_eq!(
+            analysis.unreachable,
+            [Node("11A"), Node("11B"), Node("11Z")]
+        );
+        assert_eq!(analysis.dead_ends, [Node("XXX")]);
+        assert_eq!(
+            network_structure(INPUT_2),
+            "5 strongly connected components, sizes [3, 2, 1, 1, 1]\n0 unreachable\n1 dead ends: XXX"
+        );
+        let (_, nodes): (_, HashMap<_, _>) = parse_network(INPUT_1).unwrap();
+        assert_eq!(
+            NetworkAnalysis::new(&nodes, &NodePredicate::Exact("AAA")).components,
+            [vec![Node("AAA"), Node("BBB")], vec![Node("ZZZ")]]
+        );
+    }
+
+    #[test]
+    fn dot_export() {
+        let (_, nodes): (_, HashMap<_, _>) = parse_network(INPUT_1).unwrap();
+        assert_eq!(
+            to_dot(
+                &nodes,
+                &NodePredicate::Exact("AAA"),
+                &NodePredicate::Exact("ZZZ")
+            ),
+            r#"digraph network {
+    "AAA" [style=filled, fillcolor=palegreen];
+    "ZZZ" [style=filled, fillcolor=lightcoral];
+    "AAA" -> "BBB" [label="LR"];
+    "BBB" -> "AAA" [label="L"];
+    "BBB" -> "ZZZ" [label="R"];
+    "ZZZ" -> "ZZZ" [label="LR"];
+}
+"#
+        );
+    }
+
+    #[test]
+    fn free_shortest_paths() {
+        let (_, nodes): (_, HashMap<_, _>) = parse_network(INPUT_1).unwrap();
+        let finish = NodePredicate::Exact("ZZZ");
+        assert_eq!(
+            shortest_path(&nodes, START, &finish),
+            Some(vec![Direction::Left, Direction::Right])
+        );
+        assert_eq!(shortest_path(&nodes, FINISH, &finish), Some(vec![]));
+        assert_eq!(
+            shortest_path(&nodes, FINISH, &NodePredicate::Exact("AAA")),
+            None
+        );
+        assert_eq!(free_route(INPUT_1), "2 (LR), 6 following the instructions");
+        let (_, nodes): (_, CompiledNetwork) = parse_network(INPUT_2).unwrap();
+        assert_eq!(
+            shortest_path(&nodes, Node("22A"), &GHOST_GOALS),
+            Some(vec![Direction::Left, Direction::Left, Direction::Left])
+        );
+    }
+
+    #[test]
+    fn all_pairs() {
+        let (_, nodes): (_, HashMap<_, _>) = parse_network(INPUT_1).unwrap();
+        let distances = all_distances(&nodes);
+        assert_eq!(distances.len(), 7);
+        assert_eq!(distances[&(START, START)], 0);
+        assert_eq!(distances[&(START, FINISH)], 2);
+        assert_eq!(distances[&(Node("BBB"), START)], 1);
+        assert!(!distances.contains_key(&(FINISH, START)));
+    }
 }
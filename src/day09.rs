@@ -5,6 +5,15 @@ fn delta_list(l: &[isize]) -> impl Iterator<Item = isize> + '_ {
     })
 }
 
+fn parse_history(line: &str) -> Result<Vec<isize>, HistoryError> {
+    line.split_whitespace()
+        .map(|x| {
+            x.parse()
+                .map_err(|_| HistoryError::InvalidNumber(x.to_string()))
+        })
+        .collect()
+}
+
 fn predict_next(l: &[isize]) -> isize {
     let [tail @ .., head] = l else {
         panic!("should have at least 2 elems")
@@ -20,11 +29,7 @@ fn predict_next(l: &[isize]) -> isize {
 fn part1(input: &str) -> isize {
     input
         .lines()
-        .map(|line| {
-            line.split_whitespace()
-                .map(|x| x.parse().expect("should be valid num"))
-                .collect::<Vec<_>>()
-        })
+        .map(|line| parse_history(line).expect("should be valid history"))
         .map(|x| predict_next(x.as_slice()))
         .sum()
 }
@@ -42,14 +47,416 @@ fn predict_prev(l: &[isize]) -> isize {
 
 #[aoc(day9, part2)]
 fn part2(input: &str) -> isize {
+    input
+        .lines()
+        .map(|line| parse_history(line).expect("should be valid history"))
+        .map(|x| predict_prev(x.as_slice()))
+        .sum()
+}
+
+/// Leading diagonal of the difference table, i.e. `[x0, dx0, ddx0, ...]`, without the zeros
+/// past the sequence's degree. `None` if a difference overflows an i128
+fn newton_coefficients(l: &[isize]) -> Option<Vec<i128>> {
+    let mut diffs: Vec<i128> = l.iter().map(|&x| x as i128).collect();
+    for level in 1..diffs.len() {
+        for i in (level..diffs.len()).rev() {
+            diffs[i] = diffs[i].checked_sub(diffs[i - 1])?;
+        }
+    }
+    while diffs.last() == Some(&0) {
+        diffs.pop();
+    }
+    Some(diffs)
+}
+
+/// Value of the sequence's polynomial at index `n` (can be negative or far past the end) using
+/// Newton's forward difference form `sum C(n, j) * d^j x0`, or `None` for an empty history or
+/// if it overflows an i128
+fn value_at(l: &[isize], n: i128) -> Option<i128> {
+    if l.is_empty() {
+        return None;
+    }
+    let mut binomial = 1i128;
+    let mut total = 0i128;
+    for (j, coefficient) in newton_coefficients(l)?.into_iter().enumerate() {
+        if j > 0 {
+            // C(n, j) = C(n, j - 1) * (n - j + 1) / j, exact at every step
+            binomial = binomial.checked_mul(n - j as i128 + 1)? / j as i128;
+        }
+        total = total.checked_add(binomial.checked_mul(coefficient)?)?;
+    }
+    Some(total)
+}
+
+fn predict_ahead(l: &[isize], k: u64) -> Option<i128> {
+    value_at(l, l.len() as i128 - 1 + k as i128)
+}
+
+fn predict_behind(l: &[isize], k: u64) -> Option<i128> {
+    value_at(l, -(k as i128))
+}
+
+#[aoc(day9, part1, newton)]
+fn part1_newton(input: &str) -> i128 {
+    input
+        .lines()
+        .map(|line| parse_history(line).expect("should be valid history"))
+        .map(|x| predict_ahead(x.as_slice(), 1).expect("shouldn't overflow"))
+        .sum()
+}
+
+#[aoc(day9, part2, newton)]
+fn part2_newton(input: &str) -> i128 {
+    input
+        .lines()
+        .map(|line| parse_history(line).expect("should be valid history"))
+        .map(|x| predict_behind(x.as_slice(), 1).expect("shouldn't overflow"))
+        .sum()
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Exact fraction kept in lowest terms with a positive denominator
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct Ratio {
+    num: i128,
+    den: i128,
+}
+
+impl Ratio {
+    fn new(num: i128, den: i128) -> Self {
+        let g = gcd(num, den).max(1) * den.signum();
+        Self {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    fn checked_add(self, other: Self) -> Option<Self> {
+        let g = gcd(self.den, other.den);
+        let den = (self.den / g).checked_mul(other.den)?;
+        let num = self
+            .num
+            .checked_mul(other.den / g)?
+            .checked_add(other.num.checked_mul(self.den / g)?)?;
+        Some(Self::new(num, den))
+    }
+}
+
+impl std::fmt::Display for Ratio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct PolynomialFit {
+    degree: usize,
+    /// Coefficients of n^0, n^1, ... where n is the index into the history
+    coefficients: Vec<Ratio>,
+    /// Whether a constant difference row with at least two entries backs up the degree, otherwise
+    /// the history might just be too short to tell it isn't a polynomial
+    confirmed: bool,
+}
+
+impl PolynomialFit {
+    /// `None` for an empty history or if the coefficients don't fit in an i128
+    fn new(l: &[isize]) -> Option<Self> {
+        if l.is_empty() {
+            return None;
+        }
+        let newton = newton_coefficients(l)?;
+        let degree = newton.len().saturating_sub(1);
+        let mut coefficients = vec![Ratio::new(0, 1); degree + 1];
+        // n(n - 1)...(n - j + 1) expanded into powers of n
+        let mut falling = vec![1i128];
+        let mut factorial = 1i128;
+        for (j, diff) in newton.into_iter().enumerate() {
+            if j > 0 {
+                factorial = factorial.checked_mul(j as i128)?;
+                let shift = j as i128 - 1;
+                let mut next = vec![0i128; falling.len() + 1];
+                for (power, &x) in falling.iter().enumerate() {
+                    next[power + 1] = next[power + 1].checked_add(x)?;
+                    next[power] = next[power].checked_sub(x.checked_mul(shift)?)?;
+                }
+                falling = next;
+            }
+            for (power, &x) in falling.iter().enumerate() {
+                let term = Ratio::new(x.checked_mul(diff)?, factorial);
+                coefficients[power] = coefficients[power].checked_add(term)?;
+            }
+        }
+        Some(Self {
+            degree,
+            coefficients,
+            confirmed: degree + 2 <= l.len(),
+        })
+    }
+}
+
+impl std::fmt::Display for PolynomialFit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, x)| x.num != 0)
+            .peekable();
+        if terms.peek().is_none() {
+            return write!(f, "0");
+        }
+        for (i, (power, coefficient)) in terms.enumerate() {
+            let sign = if coefficient.num < 0 { "-" } else { "+" };
+            match (i, sign) {
+                (0, "-") => write!(f, "-")?,
+                (0, _) => (),
+                _ => write!(f, " {sign} ")?,
+            }
+            let magnitude = Ratio::new(coefficient.num.abs(), coefficient.den);
+            match (power, magnitude) {
+                (0, x) => write!(f, "{x}")?,
+                (_, Ratio { num: 1, den: 1 }) => (),
+                (_, x) if x.den == 1 => write!(f, "{x}")?,
+                (_, x) => write!(f, "{x} ")?,
+            }
+            match power {
+                0 => (),
+                1 => write!(f, "n")?,
+                _ => write!(f, "n^{power}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rows of the difference table down to the first all zero (or single entry) row, or to the
+/// last one before a difference overflows
+fn difference_table(l: &[isize]) -> Vec<Vec<isize>> {
+    let mut rows = vec![l.to_vec()];
+    while let Some(last) = rows.last() {
+        if last.len() <= 1 || last.iter().all(|&x| x == 0) {
+            break;
+        }
+        let Some(next) = last.windows(2).map(|x| x[1].checked_sub(x[0])).collect() else {
+            break;
+        };
+        rows.push(next);
+    }
+    rows
+}
+
+/// The difference pyramid with the fitted polynomial underneath
+fn report(l: &[isize]) -> String {
+    use std::fmt::Write;
+    let rows = difference_table(l);
+    let width = rows
+        .iter()
+        .flatten()
+        .map(|x| x.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let mut out = String::new();
+    for (depth, row) in rows.iter().enumerate() {
+        let cells: Vec<String> = row.iter().map(|x| format!("{x:>width$}")).collect();
+        let indent = depth * (width + 1) / 2;
+        writeln!(out, "{:indent$}{}", "", cells.join(" ")).expect("should write to string");
+    }
+    match PolynomialFit::new(l) {
+        Some(fit) if fit.confirmed => writeln!(out, "p(n) = {fit}, degree {}", fit.degree),
+        Some(fit) => writeln!(
+            out,
+            "p(n) = {fit}, degree {} (not a polynomial within these samples)",
+            fit.degree
+        ),
+        None => writeln!(out, "no fit"),
+    }
+    .expect("should write to string");
+    out
+}
+
+#[aoc(day9, part1, report)]
+fn part1_report(input: &str) -> isize {
+    for line in input.lines() {
+        let l = parse_history(line).expect("should be valid history");
+        println!("{}", report(&l));
+    }
+    part1(input)
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+enum HistoryError {
+    Empty,
+    InvalidNumber(String),
+    /// No difference row had at least two entries all the same. A row only confirms the next
+    /// value once two entries agree, so a lone sample, or two samples that differ, are never
+    /// enough, unlike [`predict_next`] and [`OnlineExtrapolator`] which take a lone entry as constant
+    Exhausted,
+    Overflow,
+    /// The prediction is fine on its own but adding it to the running total overflows
+    TotalOverflow,
+}
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryError::Empty => write!(f, "empty history"),
+            HistoryError::InvalidNumber(x) => write!(f, "invalid number {x:?}"),
+            HistoryError::Exhausted => write!(f, "difference table never becomes constant"),
+            HistoryError::Overflow => write!(f, "differences overflow"),
+            HistoryError::TotalOverflow => write!(f, "total overflows"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct LineError {
+    line: usize,
+    error: HistoryError,
+}
+
+impl std::fmt::Display for LineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for LineError {}
+
+/// The edge of every difference row down to the first constant one, from the end of each row if
+/// `from_end` and the start otherwise. A row is only constant with at least two equal entries, see
+/// [`HistoryError::Exhausted`]
+fn difference_edges(l: &[isize], from_end: bool) -> Result<Vec<isize>, HistoryError> {
+    let edge = |row: &[isize]| if from_end { row[row.len() - 1] } else { row[0] };
+    if l.is_empty() {
+        return Err(HistoryError::Empty);
+    }
+    let mut row = l.to_vec();
+    let mut edges = Vec::new();
+    loop {
+        if row.len() < 2 {
+            return Err(HistoryError::Exhausted);
+        }
+        edges.push(edge(&row));
+        if row.iter().all(|&x| x == row[0]) {
+            return Ok(edges);
+        }
+        // in place so each level doesn't need its own Vec
+        for i in 0..row.len() - 1 {
+            row[i] = row[i + 1]
+                .checked_sub(row[i])
+                .ok_or(HistoryError::Overflow)?;
+        }
+        row.pop();
+    }
+}
+
+fn try_predict_next(l: &[isize]) -> Result<isize, HistoryError> {
+    difference_edges(l, true)?
+        .into_iter()
+        .try_fold(0isize, |acc, x| acc.checked_add(x))
+        .ok_or(HistoryError::Overflow)
+}
+
+fn try_predict_prev(l: &[isize]) -> Result<isize, HistoryError> {
+    difference_edges(l, false)?
+        .into_iter()
+        .rev()
+        .try_fold(0isize, |acc, x| x.checked_sub(acc))
+        .ok_or(HistoryError::Overflow)
+}
+
+fn try_solve(
+    input: &str,
+    predict: impl Fn(&[isize]) -> Result<isize, HistoryError>,
+) -> Result<isize, LineError> {
+    input
+        .lines()
+        .enumerate()
+        .try_fold(0isize, |total, (i, line)| {
+            parse_history(line)
+                .and_then(|l| predict(&l))
+                .and_then(|x| total.checked_add(x).ok_or(HistoryError::TotalOverflow))
+                .map_err(|error| LineError { line: i + 1, error })
+        })
+}
+
+#[aoc(day9, part1, checked)]
+fn part1_checked(input: &str) -> Result<isize, LineError> {
+    try_solve(input, try_predict_next)
+}
+
+#[aoc(day9, part2, checked)]
+fn part2_checked(input: &str) -> Result<isize, LineError> {
+    try_solve(input, try_predict_prev)
+}
+
+/// Extrapolates a history as it streams in, one sample at a time. Only the last entry of each
+/// difference row is kept, and rows past the first all zero one are left implicit, so a push is
+/// O(degree)
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+struct OnlineExtrapolator {
+    diagonal: Vec<isize>,
+    len: usize,
+}
+
+impl OnlineExtrapolator {
+    fn push(&mut self, x: isize) {
+        let mut carry = x;
+        for last in self.diagonal.iter_mut() {
+            let old = std::mem::replace(last, carry);
+            carry -= old;
+        }
+        // every row below was all zeros, so they all take the same new entry
+        if carry != 0 {
+            let rows = self.len + 1;
+            self.diagonal.resize(rows, carry);
+        }
+        self.len += 1;
+    }
+
+    /// `None` until there's at least one sample
+    fn predict_next(&self) -> Option<isize> {
+        (self.len > 0).then(|| self.diagonal.iter().sum())
+    }
+}
+
+impl Extend<isize> for OnlineExtrapolator {
+    fn extend<T: IntoIterator<Item = isize>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|x| self.push(x));
+    }
+}
+
+impl FromIterator<isize> for OnlineExtrapolator {
+    fn from_iter<T: IntoIterator<Item = isize>>(iter: T) -> Self {
+        let mut extrapolator = Self::default();
+        extrapolator.extend(iter);
+        extrapolator
+    }
+}
+
+#[aoc(day9, part1, online)]
+fn part1_online(input: &str) -> isize {
     input
         .lines()
         .map(|line| {
-            line.split_whitespace()
-                .map(|x| x.parse().expect("should be valid num"))
-                .collect::<Vec<_>>()
+            parse_history(line)
+                .expect("should be valid history")
+                .into_iter()
+                .collect::<OnlineExtrapolator>()
         })
-        .map(|x| predict_prev(x.as_slice()))
+        .map(|x| x.predict_next().expect("should have at least 1 elem"))
         .sum()
 }
 
@@ -70,4 +477,159 @@ mod tests {
     fn part2_example() {
         assert_eq!(part2(INPUT), 2);
     }
+
+    #[test]
+    fn newton_matches_recursive() {
+        assert_eq!(part1_newton(INPUT), 114);
+        assert_eq!(part2_newton(INPUT), 2);
+        for line in INPUT.lines() {
+            let l = parse_history(line).expect("should be valid history");
+            assert_eq!(predict_ahead(&l, 1), Some(predict_next(&l) as i128));
+            assert_eq!(predict_behind(&l, 1), Some(predict_prev(&l) as i128));
+        }
+    }
+
+    #[test]
+    fn newton_far_out() {
+        let k = 1_000_000_000;
+        assert_eq!(
+            predict_ahead(&[0, 3, 6, 9, 12, 15], k),
+            Some(15 + 3 * k as i128)
+        );
+        // triangular numbers, the n-th (0 indexed) here is (n + 1)(n + 2) / 2
+        let n = 5 + k as i128;
+        assert_eq!(
+            predict_ahead(&[1, 3, 6, 10, 15, 21], k),
+            Some((n + 1) * (n + 2) / 2)
+        );
+        assert_eq!(
+            predict_behind(&[1, 3, 6, 10, 15, 21], k),
+            Some((1 - k as i128) * (2 - k as i128) / 2)
+        );
+        assert_eq!(predict_ahead(&[7], k), Some(7));
+        assert_eq!(predict_ahead(&[], k), None);
+        assert_eq!(predict_behind(&[], k), None);
+        let wild = [
+            1, -1, 1, -1, 1, -1, 1, -1, 1, -1, 1, -1, 1, -1, 1, -1, 1, -1,
+        ];
+        assert_eq!(predict_ahead(&wild, u64::MAX), None);
+    }
+
+    #[test]
+    fn newton_difference_overflow() {
+        // each difference row doubles the swing, so i128 runs out well before the 80th
+        let extreme: Vec<isize> = (0..80)
+            .map(|i| if i % 2 == 0 { isize::MAX } else { isize::MIN })
+            .collect();
+        assert_eq!(newton_coefficients(&extreme), None);
+        assert_eq!(predict_ahead(&extreme, 1), None);
+        assert_eq!(predict_behind(&extreme, 1), None);
+        assert_eq!(PolynomialFit::new(&extreme), None);
+        assert!(report(&extreme).ends_with("no fit\n"));
+    }
+
+    #[test]
+    fn polynomial_fits() {
+        let fits: Vec<String> = INPUT
+            .lines()
+            .map(|line| {
+                let l = parse_history(line).expect("should be valid history");
+                PolynomialFit::new(&l).unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(
+            fits,
+            ["3n", "1/2 n^2 + 3/2 n + 1", "1/3 n^3 - n^2 + 11/3 n + 10"]
+        );
+        let fit = PolynomialFit::new(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(fit.degree, 3);
+        assert!(fit.confirmed);
+        let short = PolynomialFit::new(&[1, 2, 4, 8]).unwrap();
+        assert_eq!(short.degree, 3);
+        assert!(!short.confirmed);
+        assert_eq!(PolynomialFit::new(&[0, 0]).unwrap().to_string(), "0");
+        assert_eq!(PolynomialFit::new(&[-4, -4]).unwrap().to_string(), "-4");
+        assert_eq!(PolynomialFit::new(&[]), None);
+    }
+
+    #[test]
+    fn report_pyramid() {
+        assert_eq!(
+            report(&[0, 3, 6, 9, 12, 15]),
+            " 0  3  6  9 12 15\n  3  3  3  3  3\n    0  0  0  0\np(n) = 3n, degree 1\n"
+        );
+        assert!(report(&[1, 2, 4, 8]).ends_with("(not a polynomial within these samples)\n"));
+    }
+
+    #[test]
+    fn checked_matches_recursive() {
+        assert_eq!(part1_checked(INPUT), Ok(114));
+        assert_eq!(part2_checked(INPUT), Ok(2));
+    }
+
+    #[test]
+    fn checked_edge_cases() {
+        assert_eq!(try_predict_next(&[]), Err(HistoryError::Empty));
+        // one sample can't confirm anything, though the unchecked predictions take it as constant
+        assert_eq!(try_predict_next(&[7]), Err(HistoryError::Exhausted));
+        assert_eq!(try_predict_prev(&[7]), Err(HistoryError::Exhausted));
+        assert_eq!(try_predict_next(&[7, 7]), Ok(7));
+        assert_eq!(try_predict_next(&[1, 2]), Err(HistoryError::Exhausted));
+        assert_eq!(predict_next(&[1, 2]), 3);
+        assert_eq!(
+            try_predict_next(&[1, 2, 4, 8]),
+            Err(HistoryError::Exhausted)
+        );
+        assert_eq!(try_predict_next(&[1, 2, 4, 8, 15]), Ok(26));
+        assert_eq!(try_predict_prev(&[1, 2, 4, 8, 15]), Ok(0));
+        assert_eq!(
+            try_predict_next(&[isize::MIN, isize::MAX, 0]),
+            Err(HistoryError::Overflow)
+        );
+        assert_eq!(
+            part1_checked("0 3 6 9 12 15\n1 2 4 8\n1 3 6 10 15 21"),
+            Err(LineError {
+                line: 2,
+                error: HistoryError::Exhausted
+            })
+        );
+        assert_eq!(
+            part2_checked("0 3 6\n\n1 3 6").unwrap_err().to_string(),
+            "line 2: empty history"
+        );
+        assert_eq!(
+            part1_checked("0 3 x").unwrap_err().to_string(),
+            "line 1: invalid number \"x\""
+        );
+        let big = (isize::MAX / 2 + 10).to_string();
+        let big_lines = [big.as_str(); 3].map(|x| format!("{x} {x}")).join("\n");
+        assert_eq!(
+            part1_checked(&big_lines),
+            Err(LineError {
+                line: 2,
+                error: HistoryError::TotalOverflow
+            })
+        );
+    }
+
+    #[test]
+    fn online_matches_predict_next() {
+        assert_eq!(part1_online(INPUT), 114);
+        for line in INPUT
+            .lines()
+            .chain(["0 0 0 0 5 -3 7", "4", "-2 -2", "1 2 4 8 16 32"])
+        {
+            let l = parse_history(line).expect("should be valid history");
+            let mut extrapolator = OnlineExtrapolator::default();
+            assert_eq!(extrapolator.predict_next(), None);
+            for (i, &x) in l.iter().enumerate() {
+                extrapolator.push(x);
+                assert_eq!(extrapolator.predict_next(), Some(predict_next(&l[..=i])));
+            }
+        }
+        // a quadratic only ever needs three rows
+        let squares: OnlineExtrapolator = (0..1000).map(|x| x * x).collect();
+        assert_eq!(squares.diagonal.len(), 3);
+        assert_eq!(squares.predict_next(), Some(1000 * 1000));
+    }
 }
@@ -19,20 +19,6 @@ impl std::ops::Neg for Direction {
     }
 }
 
-impl std::ops::Add<(usize, usize)> for Direction {
-    type Output = (usize, usize);
-    fn add(self, rhs: (usize, usize)) -> Self::Output {
-        use Direction as D;
-        let (row, col) = rhs;
-        match self {
-            D::N => (row - 1, col),
-            D::S => (row + 1, col),
-            D::E => (row, col + 1),
-            D::W => (row, col - 1),
-        }
-    }
-}
-
 #[repr(u8)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Pipe {
@@ -74,6 +60,47 @@ impl std::ops::Neg for Pipe {
 }
 
 impl Pipe {
+    const ALL: [Pipe; 6] = [
+        Pipe::Vertical,
+        Pipe::Horizontal,
+        Pipe::NorthEast,
+        Pipe::NorthWest,
+        Pipe::SouthWest,
+        Pipe::SouthEast,
+    ];
+
+    fn openings(self) -> [Direction; 2] {
+        use Direction as D;
+        use Pipe as P;
+        match self {
+            P::Vertical => [D::N, D::S],
+            P::Horizontal => [D::E, D::W],
+            P::NorthEast => [D::N, D::E],
+            P::NorthWest => [D::N, D::W],
+            P::SouthWest => [D::S, D::W],
+            P::SouthEast => [D::S, D::E],
+        }
+    }
+
+    fn box_char(self) -> char {
+        use Pipe as P;
+        match self {
+            P::Vertical => '│',
+            P::Horizontal => '─',
+            P::NorthEast => '└',
+            P::NorthWest => '┘',
+            P::SouthWest => '┐',
+            P::SouthEast => '┌',
+        }
+    }
+
+    fn from_openings(a: Direction, b: Direction) -> Option<Self> {
+        Self::ALL.into_iter().find(|pipe| {
+            let openings = pipe.openings();
+            a != b && openings.contains(&a) && openings.contains(&b)
+        })
+    }
+
     fn try_step(self, dir_in: Direction) -> Result<Direction, anyhow::Error> {
         use anyhow::anyhow;
         use Direction as D;
@@ -132,6 +159,12 @@ impl TryFrom<char> for Tile {
     }
 }
 
+const INSIDE_SHADE: &str = "\x1b[48;5;22m";
+const OUTSIDE_SHADE: &str = "\x1b[48;5;236m";
+const DIM: &str = "\x1b[2m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
 #[derive(Debug)]
 struct Grid(Vec<Vec<Tile>>);
 
@@ -148,6 +181,102 @@ impl std::ops::IndexMut<(usize, usize)> for Grid {
     }
 }
 
+impl Grid {
+    fn get(&self, (row, col): (usize, usize)) -> Option<Tile> {
+        self.0.get(row)?.get(col).copied()
+    }
+
+    /// The neighbouring position in `dir`, or `None` if that would leave the grid
+    fn step(&self, (row, col): (usize, usize), dir: Direction) -> Option<(usize, usize)> {
+        use Direction as D;
+        let pos = match dir {
+            D::N => (row.checked_sub(1)?, col),
+            D::S => (row + 1, col),
+            D::E => (row, col + 1),
+            D::W => (row, col.checked_sub(1)?),
+        };
+        self.get(pos).map(|_| pos)
+    }
+
+    /// The pipe under the animal, worked out from which neighbours connect back to it
+    fn animal_pipe(&self, animal_pos: (usize, usize)) -> Result<Pipe, anyhow::Error> {
+        use Direction as D;
+        let connected: Vec<Direction> = [D::N, D::S, D::E, D::W]
+            .into_iter()
+            .filter(|&dir| {
+                matches!(
+                    self.step(animal_pos, dir).and_then(|pos| self.get(pos)),
+                    Some(Tile::Pipe(pipe)) if pipe.openings().contains(&-dir)
+                )
+            })
+            .collect();
+        match connected[..] {
+            [a, b] => Ok(Pipe::from_openings(a, b).expect("should be distinct directions")),
+            _ => Err(anyhow::anyhow!(
+                "animal should connect to exactly 2 neighbours, not {}",
+                connected.len()
+            )),
+        }
+    }
+
+    /// Every tile on the animal's loop in walking order, starting with the animal itself
+    fn main_loop(&self, animal_pos: (usize, usize)) -> Result<Vec<(usize, usize)>, anyhow::Error> {
+        let [mut dir, _] = self.animal_pipe(animal_pos)?.openings();
+        let mut tiles = vec![animal_pos];
+        let mut pos = animal_pos;
+        loop {
+            pos = self.step(pos, dir).ok_or_else(|| {
+                anyhow::anyhow!("loop leaves the grid at {pos:?} heading {dir:?}")
+            })?;
+            match self.get(pos) {
+                Some(Tile::Animal) => return Ok(tiles),
+                Some(Tile::Pipe(pipe)) => {
+                    dir = pipe
+                        .try_step(dir)
+                        .map_err(|e| e.context(format!("at {pos:?}")))?;
+                    tiles.push(pos);
+                }
+                _ => return Err(anyhow::anyhow!("loop is broken at {pos:?}")),
+            }
+        }
+    }
+
+    /// Draws the pipes with box-drawing characters: the main loop in bold, and every
+    /// other tile dimmed on a background shaded by whether the loop encloses it
+    fn render(&self, animal_pos: (usize, usize)) -> Result<String, anyhow::Error> {
+        let animal_pipe = self.animal_pipe(animal_pos)?;
+        let main_loop: std::collections::HashSet<_> =
+            self.main_loop(animal_pos)?.into_iter().collect();
+        let mut out = String::new();
+        for (i, row) in self.0.iter().enumerate() {
+            // crossing a loop tile that opens north flips between outside and inside
+            let mut inside = false;
+            for (j, &tile) in row.iter().enumerate() {
+                let symbol = match tile {
+                    Tile::Pipe(pipe) => pipe.box_char(),
+                    Tile::Ground => ' ',
+                    Tile::Animal => 'S',
+                };
+                if main_loop.contains(&(i, j)) {
+                    let pipe = match tile {
+                        Tile::Pipe(pipe) => pipe,
+                        _ => animal_pipe,
+                    };
+                    if pipe.openings().contains(&Direction::N) {
+                        inside = !inside;
+                    }
+                    out += &format!("{BOLD}{symbol}{RESET}");
+                } else {
+                    let shade = if inside { INSIDE_SHADE } else { OUTSIDE_SHADE };
+                    out += &format!("{shade}{DIM}{symbol}{RESET}");
+                }
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
 #[aoc_generator(day10)]
 fn parse(input: &str) -> (Grid, (usize, usize)) {
     let mut animal_pos = std::cell::OnceCell::new();
@@ -175,47 +304,22 @@ fn parse(input: &str) -> (Grid, (usize, usize)) {
 
 #[aoc(day10, part1)]
 fn part1((grid, animal_pos): &(Grid, (usize, usize))) -> usize {
-    // println!("{grid:#?}");
-    // println!("Animal at {animal_pos:?}");
-    // let start_pos = Direction::E + animal_pos;
-    // let start @ Tile::Pipe(_) = grid[start_pos] else {
-    //     unimplemented!("animal that cant go E")
-    // };
-    let mut count = 1;
-    let mut dir = Direction::E;
-    let mut pos = dir + *animal_pos;
-    while let Tile::Pipe(next) = grid[pos] {
-        // println!("{pos:?} {dir:?} {next:?}");
-        dir = next.try_step(dir).expect("should be valid step");
-        pos = dir + pos;
-        count += 1;
-    }
-    count / 2
+    grid.main_loop(*animal_pos)
+        .expect("should have a closed loop")
+        .len()
+        / 2
 }
 
 #[aoc(day10, part2)]
 fn part2((grid, animal_pos): &(Grid, (usize, usize))) -> usize {
-    let mut main_loop_coords = std::collections::HashSet::with_capacity(6823 * 2);
-    let mut dir = if let Tile::Pipe(Pipe::SouthEast) = grid[Direction::W + *animal_pos] {
-        Direction::W
-    } else {
-        Direction::E
-    }; // effectively hardcoded w/exceptions for the examples
-    let mut pos = dir + *animal_pos;
-    while let Tile::Pipe(next) = grid[pos] {
-        main_loop_coords.insert(pos);
-        dir = next.try_step(dir).expect("should be valid step");
-        pos = dir + pos;
-    }
-    let animal_pipe: Pipe = {
-        // effectively hardcoded w/exceptions for the examples
-        match grid[-dir + pos] {
-            Tile::Pipe(Pipe::Vertical) => Pipe::SouthWest,
-            Tile::Pipe(Pipe::NorthWest) => Pipe::SouthEast,
-            _ => Pipe::Horizontal,
-        }
-    };
-    // println!("{animal_pipe:?}");
+    let animal_pipe = grid
+        .animal_pipe(*animal_pos)
+        .expect("should know the animal's pipe");
+    let main_loop_coords: std::collections::HashSet<_> = grid
+        .main_loop(*animal_pos)
+        .expect("should have a closed loop")
+        .into_iter()
+        .collect();
     grid.0
         .iter()
         .enumerate()
@@ -265,6 +369,227 @@ fn part2((grid, animal_pos): &(Grid, (usize, usize))) -> usize {
         .sum()
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ComponentKind {
+    /// Every tile connects at both ends
+    Loop,
+    /// At least one end leads nowhere
+    Chain,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Component {
+    kind: ComponentKind,
+    tiles: std::collections::BTreeSet<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct PipeAnalysis {
+    /// Connected runs of pipe, biggest first
+    components: Vec<Component>,
+    /// Pipe ends pointing into a pipe that can't be entered from that side
+    incompatible: Vec<((usize, usize), Direction)>,
+}
+
+impl PipeAnalysis {
+    /// Pipes are linked when both open towards each other. The animal stands in for its
+    /// inferred pipe, or for no pipe at all if that can't be worked out
+    fn new(grid: &Grid, animal_pos: (usize, usize)) -> Self {
+        let animal_pipe = grid.animal_pipe(animal_pos).ok();
+        let pipe_at = |pos| match grid.get(pos)? {
+            Tile::Pipe(pipe) => Some(pipe),
+            Tile::Animal => animal_pipe,
+            Tile::Ground => None,
+        };
+        let mut incompatible = Vec::new();
+        let mut links = std::collections::HashMap::new();
+        for (i, row) in grid.0.iter().enumerate() {
+            for j in 0..row.len() {
+                let Some(pipe) = pipe_at((i, j)) else {
+                    continue;
+                };
+                let mut linked = Vec::with_capacity(2);
+                for dir in pipe.openings() {
+                    let Some(next) = grid.step((i, j), dir) else {
+                        continue;
+                    };
+                    match pipe_at(next).map(|other| other.try_step(dir)) {
+                        Some(Ok(_)) => linked.push(next),
+                        Some(Err(_)) => incompatible.push(((i, j), dir)),
+                        None => (),
+                    }
+                }
+                links.insert((i, j), linked);
+            }
+        }
+        let mut seen = std::collections::HashSet::new();
+        let mut components = Vec::new();
+        for (i, row) in grid.0.iter().enumerate() {
+            for j in 0..row.len() {
+                if !links.contains_key(&(i, j)) || !seen.insert((i, j)) {
+                    continue;
+                }
+                let mut tiles = std::collections::BTreeSet::new();
+                let mut stack = vec![(i, j)];
+                while let Some(pos) = stack.pop() {
+                    tiles.insert(pos);
+                    stack.extend(links[&pos].iter().filter(|&&next| seen.insert(next)));
+                }
+                let kind = if tiles.iter().all(|pos| links[pos].len() == 2) {
+                    ComponentKind::Loop
+                } else {
+                    ComponentKind::Chain
+                };
+                components.push(Component { kind, tiles });
+            }
+        }
+        components.sort_by_key(|c| std::cmp::Reverse(c.tiles.len()));
+        Self {
+            components,
+            incompatible,
+        }
+    }
+}
+
+impl std::fmt::Display for PipeAnalysis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let count = |kind| self.components.iter().filter(|c| c.kind == kind).count();
+        writeln!(
+            f,
+            "{} closed loops, {} dangling chains, {} incompatible pipe ends",
+            count(ComponentKind::Loop),
+            count(ComponentKind::Chain),
+            self.incompatible.len()
+        )?;
+        for component in &self.components {
+            let tiles: Vec<_> = component
+                .tiles
+                .iter()
+                .map(|(row, col)| format!("({row}, {col})"))
+                .collect();
+            writeln!(
+                f,
+                "{:?} of {}: {}",
+                component.kind,
+                component.tiles.len(),
+                tiles.join(" ")
+            )?;
+        }
+        for ((row, col), dir) in &self.incompatible {
+            writeln!(
+                f,
+                "({row}, {col}) points {dir:?} into a pipe that doesn't fit"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Every loop, dangling chain and mismatched pipe end in the grid, as text
+fn pipe_report(grid: &Grid, animal_pos: (usize, usize)) -> String {
+    PipeAnalysis::new(grid, animal_pos).to_string()
+}
+
+#[aoc(day10, part2, render)]
+fn part2_render(input: &(Grid, (usize, usize))) -> usize {
+    let (grid, animal_pos) = input;
+    print!(
+        "{}",
+        grid.render(*animal_pos).expect("should have a closed loop")
+    );
+    part2_shoelace(input)
+}
+
+/// Shortest distance from the animal along the loop, either way round, for every tile; `None` off the loop
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct DistanceMap(Vec<Vec<Option<usize>>>);
+
+impl DistanceMap {
+    fn new(grid: &Grid, animal_pos: (usize, usize)) -> Result<Self, anyhow::Error> {
+        let tiles = grid.main_loop(animal_pos)?;
+        let mut distances: Vec<Vec<Option<usize>>> =
+            grid.0.iter().map(|row| vec![None; row.len()]).collect();
+        for (i, &(row, col)) in tiles.iter().enumerate() {
+            distances[row][col] = Some(i.min(tiles.len() - i));
+        }
+        Ok(DistanceMap(distances))
+    }
+
+    /// The position and distance of the tile farthest from the animal, first in reading order on ties
+    fn farthest(&self) -> Option<((usize, usize), usize)> {
+        self.0
+            .iter()
+            .enumerate()
+            .flat_map(|(i, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter_map(move |(j, &d)| d.map(|d| ((i, j), d)))
+            })
+            .rev()
+            .max_by_key(|&(_, d)| d)
+    }
+
+    /// Each loop tile as a block shaded from blue (near the animal) to red (farthest away)
+    fn heat_map(&self) -> String {
+        let max = self.farthest().map_or(0, |(_, d)| d).max(1);
+        let mut out = String::new();
+        for row in &self.0 {
+            for d in row {
+                match d {
+                    Some(d) => {
+                        let heat = d * 255 / max;
+                        out += &format!("\x1b[38;2;{heat};0;{}m█", 255 - heat);
+                    }
+                    None => out += &format!("{RESET} "),
+                }
+            }
+            out += &format!("{RESET}\n");
+        }
+        out
+    }
+}
+
+impl std::fmt::Display for DistanceMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.farthest().map_or(1, |(_, d)| d.to_string().len());
+        for row in &self.0 {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|d| match d {
+                    Some(d) => format!("{d:>width$}"),
+                    None => format!("{:>width$}", "."),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[aoc(day10, part1, heat_map)]
+fn part1_heat_map((grid, animal_pos): &(Grid, (usize, usize))) -> usize {
+    let distances = DistanceMap::new(grid, *animal_pos).expect("should have a closed loop");
+    print!("{}", distances.heat_map());
+    let ((row, col), distance) = distances.farthest().expect("loop should not be empty");
+    println!("farthest tile is at row {row}, column {col}");
+    distance
+}
+
+/// Pick's theorem on the loop polygon: `A = i + b/2 - 1`, with the area `A` from the shoelace formula
+#[aoc(day10, part2, shoelace)]
+fn part2_shoelace((grid, animal_pos): &(Grid, (usize, usize))) -> usize {
+    let tiles = grid
+        .main_loop(*animal_pos)
+        .expect("should have a closed loop");
+    let twice_area = tiles
+        .iter()
+        .zip(tiles.iter().cycle().skip(1))
+        .map(|(&(r1, c1), &(r2, c2))| (r1 * c2) as isize - (r2 * c1) as isize)
+        .sum::<isize>()
+        .unsigned_abs();
+    (twice_area + 2 - tiles.len()) / 2
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,6 +660,184 @@ mod tests {
         assert_eq!(part2(&parse(INPUT_2)), 10);
     }
 
+    #[test]
+    fn part2_shoelace_matches_scan() {
+        for input in [
+            INPUT_1,
+            INPUT_2_BASIC_BASIC,
+            INPUT_2_BASIC,
+            INPUT_2,
+            "S-7\n|.|\nL-J",
+            "......\n.F--7.\n.|..|.\n.S--J.\n......",
+            "S--7\n|..|\n|..|\nL--J",
+            "F7.F7\n|L-J|\nL---S",
+        ] {
+            let parsed = parse(input);
+            assert_eq!(part2_shoelace(&parsed), part2(&parsed), "{input}");
+        }
+    }
+
+    #[test]
+    fn distance_map() {
+        let (grid, animal_pos) = parse(INPUT_1);
+        let distances = DistanceMap::new(&grid, animal_pos).unwrap();
+        assert_eq!(distances.farthest(), Some(((2, 4), 8)));
+        assert_eq!(
+            distances.to_string(),
+            "\
+            . . 4 5 .\n\
+            . 2 3 6 .\n\
+            0 1 . 7 8\n\
+            1 4 5 6 7\n\
+            2 3 . . .\n"
+        );
+        assert_eq!(part1_heat_map(&(grid, animal_pos)), 8);
+        for input in [INPUT_2_BASIC_BASIC, INPUT_2_BASIC, INPUT_2] {
+            let parsed = parse(input);
+            let distances = DistanceMap::new(&parsed.0, parsed.1).unwrap();
+            assert_eq!(distances.farthest().unwrap().1, part1(&parsed));
+        }
+    }
+
+    #[test]
+    fn render_box_drawing() {
+        let (grid, animal_pos) = parse(INPUT_2_BASIC_BASIC);
+        let rendered = grid.render(animal_pos).unwrap();
+        let plain: String = rendered
+            .split('\x1b')
+            .map(|chunk| chunk.split_once('m').map_or(chunk, |(_, rest)| rest))
+            .collect();
+        assert_eq!(plain.lines().nth(1), Some(" S───────┐ "));
+        assert_eq!(plain.lines().nth(5), Some(" │└─┐ ┌─┘│ "));
+        for input in [INPUT_1, INPUT_2_BASIC_BASIC, INPUT_2_BASIC, INPUT_2] {
+            let parsed = parse(input);
+            let rendered = parsed.0.render(parsed.1).unwrap();
+            assert_eq!(rendered.matches(INSIDE_SHADE).count(), part2(&parsed));
+        }
+    }
+
+    #[test]
+    fn pipe_analysis() {
+        //noinspection SpellCheckingInspection
+        let (grid, animal_pos) = parse(
+            "\
+            S-7.F7\n\
+            |.|.LJ\n\
+            L-J-L7\n\
+            .-F|..",
+        );
+        let analysis = PipeAnalysis::new(&grid, animal_pos);
+        let summary: Vec<_> = analysis
+            .components
+            .iter()
+            .map(|c| (c.kind, c.tiles.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (ComponentKind::Loop, 8),
+                (ComponentKind::Loop, 4),
+                (ComponentKind::Chain, 2),
+                (ComponentKind::Chain, 1),
+                (ComponentKind::Chain, 1),
+                (ComponentKind::Chain, 1),
+                (ComponentKind::Chain, 1),
+            ]
+        );
+        assert_eq!(
+            analysis.components[1]
+                .tiles
+                .iter()
+                .copied()
+                .collect::<Vec<_>>(),
+            [(0, 4), (0, 5), (1, 4), (1, 5)]
+        );
+        assert_eq!(
+            analysis.incompatible,
+            [
+                ((2, 3), Direction::E),
+                ((2, 3), Direction::W),
+                ((2, 4), Direction::N),
+                ((3, 1), Direction::E),
+                ((3, 2), Direction::E),
+                ((3, 3), Direction::N)
+            ]
+        );
+        assert!(pipe_report(&grid, animal_pos)
+            .starts_with("2 closed loops, 5 dangling chains, 6 incompatible pipe ends\n"));
+
+        let (grid, animal_pos) = parse(INPUT_2);
+        let analysis = PipeAnalysis::new(&grid, animal_pos);
+        let main = grid.main_loop(animal_pos).unwrap();
+        assert!(analysis
+            .components
+            .iter()
+            .any(|c| c.kind == ComponentKind::Loop
+                && c.tiles.len() == main.len()
+                && main.iter().all(|pos| c.tiles.contains(pos))));
+    }
+
+    #[test]
+    fn infer_animal_pipe() {
+        let pipe = |input: &str| {
+            let (grid, animal_pos) = parse(input);
+            grid.animal_pipe(animal_pos)
+        };
+        assert_eq!(pipe(INPUT_1).unwrap(), Pipe::SouthEast);
+        assert_eq!(pipe(INPUT_2_BASIC_BASIC).unwrap(), Pipe::SouthEast);
+        assert_eq!(pipe(INPUT_2_BASIC).unwrap(), Pipe::SouthEast);
+        assert_eq!(pipe(INPUT_2).unwrap(), Pipe::SouthWest);
+        assert_eq!(
+            pipe("-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF").unwrap(),
+            Pipe::SouthEast
+        );
+        assert_eq!(pipe(".|.\n-S.\n...").unwrap(), Pipe::NorthWest);
+        assert!(pipe("...\n.S.\n...").is_err());
+        assert!(pipe(".|.\n-S-\n.|.").is_err());
+        assert!(pipe("...\n.S-\n...").is_err());
+    }
+
+    #[test]
+    fn animal_pipe_any_orientation() {
+        // the same loop with the animal on each corner
+        assert_eq!(part1(&parse("S-7\n|.|\nL-J")), 4);
+        assert_eq!(part1(&parse("F-S\n|.|\nL-J")), 4);
+        assert_eq!(part1(&parse(".....\n.F-7.\n.|.|.\n.L-S.\n.....")), 4);
+        assert_eq!(part2(&parse(".....\n.F-7.\n.|.|.\n.L-S.\n.....")), 1);
+        assert_eq!(part2(&parse("......\n.F--7.\n.|..|.\n.S--J.\n......")), 2);
+    }
+
+    #[test]
+    fn grid_step_bounds() {
+        let (grid, _) = parse(INPUT_1);
+        assert_eq!(grid.step((0, 0), Direction::N), None);
+        assert_eq!(grid.step((0, 0), Direction::W), None);
+        assert_eq!(grid.step((4, 4), Direction::S), None);
+        assert_eq!(grid.step((4, 4), Direction::E), None);
+        assert_eq!(grid.step((4, 4), Direction::N), Some((3, 4)));
+        assert_eq!(grid.step((0, 0), Direction::E), Some((0, 1)));
+        assert_eq!(grid.get((5, 0)), None);
+    }
+
+    #[test]
+    fn loop_along_border() {
+        //noinspection SpellCheckingInspection
+        const BORDER: &str = "\
+        S--7\n\
+        |..|\n\
+        |..|\n\
+        L--J";
+        assert_eq!(part1(&parse(BORDER)), 6);
+        assert_eq!(part2(&parse(BORDER)), 4);
+        //noinspection SpellCheckingInspection
+        const BORDER_ANIMAL_CORNER: &str = "\
+        F7.F7\n\
+        |L-J|\n\
+        L---S";
+        assert_eq!(part1(&parse(BORDER_ANIMAL_CORNER)), 7);
+        assert_eq!(part2(&parse(BORDER_ANIMAL_CORNER)), 0);
+    }
+
     #[test]
     fn part2_check_with_answers() {
         use std::fs::read_to_string;
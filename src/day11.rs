@@ -37,17 +37,16 @@ impl std::fmt::Display for Coords {
 
 fn solve(input: &str, expansion_size: usize) -> usize {
     let tiles = input.lines().map(str::as_bytes).collect::<Vec<_>>();
-    let line_len_initial = tiles.len();
-    let n_cols_initial = tiles[0].len();
-    let blank_rows: Vec<usize> = (0..n_cols_initial)
-        .filter(|&i| (0..line_len_initial).all(|j| tiles[i][j] == b'.'))
+    let n_rows = tiles.len();
+    let n_cols = tiles[0].len();
+    let blank_rows: Vec<usize> = (0..n_rows)
+        .filter(|&i| (0..n_cols).all(|j| tiles[i][j] == b'.'))
         .collect();
-    // println!("{blank_rows:?}");
-    let blank_cols: Vec<usize> = (0..line_len_initial)
-        .filter(|&j| (0..n_cols_initial).all(|i| tiles[i][j] == b'.'))
+    let blank_cols: Vec<usize> = (0..n_cols)
+        .filter(|&j| (0..n_rows).all(|i| tiles[i][j] == b'.'))
         .collect();
-    let galaxies = (0..line_len_initial)
-        .flat_map(|i| (0..n_cols_initial).map(move |j| (i, j)))
+    let galaxies = (0..n_rows)
+        .flat_map(|i| (0..n_cols).map(move |j| (i, j)))
         .filter(|&(i, j)| tiles[i][j] == b'#')
         .map(|(i, j)| {
             Coords(i, j)
@@ -64,6 +63,170 @@ fn solve(input: &str, expansion_size: usize) -> usize {
         .sum()
 }
 
+/// Where each original line ends up once every empty line has grown to `expansion_size`,
+/// from a running count of the empty lines before it
+fn expanded_positions(occupied: &[bool], expansion_size: u128) -> Vec<u128> {
+    let mut empty_before = 0;
+    occupied
+        .iter()
+        .enumerate()
+        .map(|(i, &occupied)| {
+            let pos = i as u128 + empty_before * (expansion_size - 1);
+            if !occupied {
+                empty_before += 1;
+            }
+            pos
+        })
+        .collect()
+}
+
+/// Sum of `|a - b|` over all pairs, each sorted value being `i` pairs' larger side
+fn pairwise_distance_sum(sorted: &[u128]) -> u128 {
+    let mut prefix_sum = 0;
+    sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let total = x * i as u128 - prefix_sum;
+            prefix_sum += x;
+            total
+        })
+        .sum()
+}
+
+/// Same as [`solve`], summing each axis separately over sorted coordinates in O(n log n)
+fn solve_sorted(input: &str, expansion_size: usize) -> usize {
+    let expansion_size = expansion_size as u128;
+    solve_expanded(input, expansion_size, expansion_size)
+        .try_into()
+        .expect("total should fit in a usize")
+}
+
+/// Every galaxy's position in the image next to its expanded row and column, in reading order,
+/// when empty rows grow to `row_expansion` rows and empty columns to `col_expansion` columns
+fn expand_galaxies(
+    input: &str,
+    row_expansion: u128,
+    col_expansion: u128,
+) -> Vec<(Coords, (u128, u128))> {
+    let tiles = input.lines().map(str::as_bytes).collect::<Vec<_>>();
+    let n_cols = tiles.first().map_or(0, |row| row.len());
+    assert!(
+        tiles.iter().all(|row| row.len() == n_cols),
+        "image should be rectangular"
+    );
+    let galaxies: Vec<Coords> = tiles
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|&(_, &x)| x == b'#')
+                .map(move |(j, _)| Coords(i, j))
+        })
+        .collect();
+    let mut occupied_rows = vec![false; tiles.len()];
+    let mut occupied_cols = vec![false; n_cols];
+    for &Coords(i, j) in &galaxies {
+        occupied_rows[i] = true;
+        occupied_cols[j] = true;
+    }
+    let row_pos = expanded_positions(&occupied_rows, row_expansion);
+    let col_pos = expanded_positions(&occupied_cols, col_expansion);
+    galaxies
+        .into_iter()
+        .map(|galaxy @ Coords(i, j)| (galaxy, (row_pos[i], col_pos[j])))
+        .collect()
+}
+
+/// Total distance between all galaxy pairs when empty rows grow to `row_expansion` rows
+/// and empty columns to `col_expansion` columns
+fn solve_expanded(input: &str, row_expansion: u128, col_expansion: u128) -> u128 {
+    let galaxies = expand_galaxies(input, row_expansion, col_expansion);
+    let mut rows: Vec<u128> = galaxies.iter().map(|&(_, (i, _))| i).collect();
+    let mut cols: Vec<u128> = galaxies.iter().map(|&(_, (_, j))| j).collect();
+    rows.sort_unstable();
+    cols.sort_unstable();
+    pairwise_distance_sum(&rows) + pairwise_distance_sum(&cols)
+}
+
+/// The galaxies after expansion, numbered in reading order of the original image
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Universe {
+    original: Vec<Coords>,
+    galaxies: Vec<Coords>,
+}
+
+impl Universe {
+    /// Expanded positions have to fit in a usize, unlike with [`solve_expanded`]
+    fn new(input: &str, row_expansion: u128, col_expansion: u128) -> Self {
+        let to_usize = |x: u128| usize::try_from(x).expect("position should fit in a usize");
+        let (original, galaxies) = expand_galaxies(input, row_expansion, col_expansion)
+            .into_iter()
+            .map(|(galaxy, (i, j))| (galaxy, Coords(to_usize(i), to_usize(j))))
+            .unzip();
+        Self { original, galaxies }
+    }
+
+    fn distance(&self, a: usize, b: usize) -> Option<usize> {
+        Some(self.galaxies.get(a)?.manhattan_dist(self.galaxies.get(b)?))
+    }
+
+    /// Index of the galaxy at `coords` in the original image
+    fn index_of(&self, coords: Coords) -> Option<usize> {
+        self.original.iter().position(|&x| x == coords)
+    }
+
+    /// Distance between the galaxies at `a` and `b` in the original image
+    fn distance_between(&self, a: Coords, b: Coords) -> Option<usize> {
+        self.distance(self.index_of(a)?, self.index_of(b)?)
+    }
+
+    /// Other galaxies as `(index, distance)` from galaxy `i`
+    fn neighbours(&self, i: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let from = self.galaxies.get(i);
+        self.galaxies
+            .iter()
+            .enumerate()
+            .filter(move |&(j, _)| j != i)
+            .filter_map(move |(j, x)| Some((j, from?.manhattan_dist(x))))
+    }
+
+    /// Closest other galaxy to galaxy `i` as `(index, distance)`, lowest index on ties
+    fn nearest(&self, i: usize) -> Option<(usize, usize)> {
+        self.neighbours(i).min_by_key(|&(_, d)| d)
+    }
+
+    /// Farthest other galaxy from galaxy `i` as `(index, distance)`, lowest index on ties
+    fn farthest(&self, i: usize) -> Option<(usize, usize)> {
+        self.neighbours(i)
+            .max_by_key(|&(j, d)| (d, std::cmp::Reverse(j)))
+    }
+
+    /// The two galaxies farthest apart and their distance. Manhattan distance is the larger
+    /// of the spreads of `row + col` and `row - col`, so this is a single pass
+    fn diameter(&self) -> Option<(usize, usize, usize)> {
+        let sum = |i: &usize| self.galaxies[*i].0 + self.galaxies[*i].1;
+        let diff = |i: &usize| self.galaxies[*i].0 as isize - self.galaxies[*i].1 as isize;
+        let indices = 0..self.galaxies.len();
+        let by_sum = (
+            indices.clone().min_by_key(sum)?,
+            indices.clone().max_by_key(sum)?,
+        );
+        let by_diff = (indices.clone().min_by_key(diff)?, indices.max_by_key(diff)?);
+        [by_sum, by_diff]
+            .into_iter()
+            .map(|(a, b)| {
+                (
+                    a.min(b),
+                    a.max(b),
+                    self.galaxies[a].manhattan_dist(&self.galaxies[b]),
+                )
+            })
+            .max_by_key(|&(_, _, d)| d)
+    }
+}
+
 #[aoc(day11, part1)]
 fn part1(input: &str) -> usize {
     solve(input, 2)
@@ -74,6 +237,16 @@ fn part2(input: &str) -> usize {
     solve(input, 1_000_000)
 }
 
+#[aoc(day11, part1, sorted)]
+fn part1_sorted(input: &str) -> usize {
+    solve_sorted(input, 2)
+}
+
+#[aoc(day11, part2, sorted)]
+fn part2_sorted(input: &str) -> usize {
+    solve_sorted(input, 1_000_000)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,4 +324,86 @@ mod tests {
         assert_eq!(solve(INPUT_1, 10), 1030);
         assert_eq!(solve(INPUT_1, 100), 8410);
     }
+
+    #[test]
+    fn sorted_matches_solve() {
+        for expansion_size in [1, 2, 10, 100, 1_000_000] {
+            assert_eq!(
+                solve_sorted(INPUT_1, expansion_size),
+                solve(INPUT_1, expansion_size)
+            );
+        }
+        assert_eq!(part1_sorted(INPUT_1), 374);
+    }
+
+    #[test]
+    fn rectangular_images() {
+        //noinspection SpellCheckingInspection
+        const WIDE: &str = "\
+        #...#\n\
+        .....\n\
+        ..#..";
+        assert_eq!(solve(WIDE, 2), 18);
+        assert_eq!(solve_sorted(WIDE, 2), 18);
+        let tall = "#.\n..\n..\n.#\n#.";
+        assert_eq!(solve(tall, 3), solve_sorted(tall, 3));
+        assert_eq!(solve_sorted(tall, 3), 8 + 8 + 2);
+    }
+
+    #[test]
+    fn per_axis_expansion() {
+        const WIDE: &str = "#...#\n.....\n..#..";
+        assert_eq!(solve_expanded(WIDE, 10, 1), 30);
+        assert_eq!(solve_expanded(WIDE, 1, 10), 22 + 13 + 13);
+        assert_eq!(solve_expanded(INPUT_1, 10, 10), 1030);
+        let huge = 10u128.pow(30);
+        // columns end up at 0, huge + 1 and 2 * huge + 2, rows at 0 and huge + 1
+        assert_eq!(
+            solve_expanded(WIDE, huge, huge),
+            (2 * huge + 2) + 2 * (huge + 1) + 2 * (huge + 1)
+        );
+    }
+
+    #[test]
+    fn universe_queries() {
+        let universe = Universe::new(INPUT_1, 2, 2);
+        // galaxies 5 and 9 in the puzzle's 1-based numbering
+        assert_eq!(universe.distance(4, 8), Some(9));
+        assert_eq!(universe.distance(0, 6), Some(15));
+        assert_eq!(universe.distance(2, 5), Some(17));
+        assert_eq!(universe.distance(7, 8), Some(5));
+        assert_eq!(universe.distance(0, 9), None);
+        assert_eq!(
+            universe.distance_between(Coords(0, 3), Coords(8, 7)),
+            Some(15)
+        );
+        assert_eq!(universe.distance_between(Coords(0, 0), Coords(8, 7)), None);
+        assert_eq!(universe.nearest(7), Some((8, 5)));
+        assert_eq!(universe.nearest(9), None);
+        let total_distance = |universe: &Universe| {
+            let n = universe.galaxies.len();
+            (0..n)
+                .flat_map(|a| (a + 1..n).map(move |b| (a, b)))
+                .filter_map(|(a, b)| universe.distance(a, b))
+                .sum::<usize>()
+        };
+        assert_eq!(total_distance(&universe), 374);
+        assert_eq!(
+            total_distance(&Universe::new(INPUT_1, 10, 100)) as u128,
+            solve_expanded(INPUT_1, 10, 100)
+        );
+        let brute_max = (0..universe.galaxies.len())
+            .filter_map(|i| universe.farthest(i))
+            .map(|(_, d)| d)
+            .max();
+        assert_eq!(universe.diameter().map(|(_, _, d)| d), brute_max);
+        let (a, b, d) = universe.diameter().unwrap();
+        assert_eq!(universe.distance(a, b), Some(d));
+    }
+
+    #[test]
+    #[should_panic(expected = "image should be rectangular")]
+    fn universe_rejects_ragged_images() {
+        Universe::new("#.\n..#", 2, 2);
+    }
 }